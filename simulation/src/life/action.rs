@@ -0,0 +1,60 @@
+use crate::world::{Entity, Level};
+
+/// What a creature decided to do this tick
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum Action {
+    Wait,
+    Move((usize, usize)),
+    Attack((usize, usize)),
+    Mate((usize, usize)),
+    Eat((usize, usize)),
+    PickUp((usize, usize)),
+    Dig((usize, usize)),
+    /// Go through the stairs one's standing on
+    TakeStairs,
+}
+
+/// From where to where a creature moves
+pub type Move = ((usize, usize), (usize, usize));
+
+#[derive(Debug, Copy, Clone)]
+pub struct Intent {
+    pub from: (usize, usize),
+    pub action: Action,
+}
+
+/// Whether there's a creature at `pos` that wasn't killed earlier in the tick, and so still gets to do what it meant to
+pub fn is_alive<const SIZE: usize>(level: &Level<SIZE>, pos: (usize, usize)) -> bool {
+    matches!(&level[pos].entity, Some(Entity::Creature(c)) if c.body.health > 0)
+}
+
+/// Every destination can be claimed by at most one creature, and only if it was free when the tick started.
+/// Conflicts are won by the claimant that comes first in slot order, so the outcome only depends on the level.
+/// Creatures killed earlier in the tick don't move.
+pub fn resolve_moves<const SIZE: usize>(
+    level: &Level<SIZE>,
+    intents: &[Intent],
+) -> Vec<Move> {
+    let mut moves: Vec<Move> = intents.iter()
+        .filter_map(|i| match i.action {
+            Action::Move(to) if to != i.from && is_alive(level, i.from) => Some((i.from, to)),
+            _ => None,
+        })
+        .filter(|(_, to)| {
+            let slot = &level[*to];
+            slot.tile.is_floor() && slot.entity.is_none()
+        })
+        .collect();
+    moves.sort_by_key(|(from, to)| (to.0 * SIZE + to.1, from.0 * SIZE + from.1));
+    moves.dedup_by_key(|(_, to)| *to);
+    moves
+}
+
+pub fn apply_moves<const SIZE: usize>(
+    level: &mut Level<SIZE>,
+    moves: &[Move],
+) {
+    for (from, to) in moves {
+        level.swap_entities(*from, *to);
+    }
+}
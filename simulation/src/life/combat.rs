@@ -0,0 +1,58 @@
+use crate::{world::{Level, Entity, creature::{Creature, SpeciesMap}}, util::FastRandom};
+
+use super::relations::hold_grudge;
+
+/// Two rolls up to `strength` each, so hits average `strength` but rarely land that far from it
+pub fn roll_damage(random: &mut FastRandom, strength: u8) -> u8 {
+    let roll = |random: &mut FastRandom| random.next_less_than(strength as u64 + 1);
+    (roll(random) + roll(random)).min(u8::MAX as u64) as u8
+}
+
+/// Hurts `defender`, who won't forget it, and shifts the karma of `attacker`:
+/// picking on peaceful creatures is frowned upon, standing up to aggressive ones isn't. Returns the damage dealt.
+pub fn attack(random: &mut FastRandom, attacker: &mut Creature, defender: &mut Creature) -> u8 {
+    let damage = roll_damage(random, attacker.body.strength);
+    defender.body.health = defender.body.health.saturating_sub(damage);
+    hold_grudge(defender, attacker.id);
+    defender.memory.asleep = false;
+    attacker.body.karma = if defender.body.karma < 0 {
+        attacker.body.karma.saturating_add(1)
+    } else {
+        attacker.body.karma.saturating_sub(1)
+    };
+    damage
+}
+
+/// The creature at `from` hits the one at `to`, and eats it if it dies and the attacker eats meat.
+/// Dead creatures are left for `life::tick` to clear up. Returns whether the defender got killed.
+pub fn fight<const SIZE: usize>(
+    level: &mut Level<SIZE>,
+    species: &SpeciesMap,
+    random: &mut FastRandom,
+    from: (usize, usize),
+    to: (usize, usize),
+) -> bool {
+    let mut attacker = match level[from].entity.take() {
+        Some(Entity::Creature(c)) if c.body.health > 0 => c,
+        e => {
+            level[from].entity = e;
+            return false;
+        },
+    };
+    let killed = match &mut level[to].entity {
+        Some(Entity::Creature(defender)) if defender.body.health > 0 => {
+            attack(random, &mut attacker, defender);
+            if defender.body.health == 0 {
+                if species[&attacker.species].diet.meat {
+                    attacker.body.nutrition = attacker.body.nutrition
+                        .saturating_add(defender.body.max_nutrition / 2)
+                        .min(attacker.body.max_nutrition);
+                }
+                true
+            } else { false }
+        },
+        _ => false,
+    };
+    level[from].entity = Some(Entity::Creature(attacker));
+    killed
+}
@@ -0,0 +1,132 @@
+use crate::{world::{Level, SlotPosition, Tile, creature::{Creature, PerceivedKind}}, util::FastRandom};
+
+use super::closest_portal_tile;
+
+/// How many of the last visited positions a creature remembers
+const EXPLORED_MEMORY: usize = 32;
+
+/// Tiles closer than this to a remembered position count as explored
+const EXPLORED_RADIUS: usize = 3;
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+enum Candidate {
+    Unexplored,
+    Object,
+    Creature,
+    Portal,
+}
+
+impl Candidate {
+    /// Plain tiles are always somewhat interesting, the rest gets more appealing the more curious one is
+    fn weight(&self, curiosity: u8) -> u64 {
+        let curiosity = curiosity as u64;
+        match self {
+            Candidate::Unexplored => 64,
+            Candidate::Object => 32 + curiosity / 2,
+            Candidate::Creature => 16 + curiosity,
+            Candidate::Portal => curiosity / 2,
+        }
+    }
+}
+
+pub fn remember_position(c: &mut Creature, pos: (usize, usize)) {
+    let explored = &mut c.memory.explored;
+    if explored.last() != Some(&pos) {
+        if explored.len() >= EXPLORED_MEMORY {
+            explored.remove(0);
+        }
+        explored.push(pos);
+    }
+}
+
+/// Drops the target once it's been reached or the creature has been after it for too long
+pub fn update_target<const SIZE: usize>(
+    c: &mut Creature,
+    level: &Level<SIZE>,
+    l: usize,
+    pos: (usize, usize),
+) {
+    let target = match c.memory.target {
+        Some(t) => t,
+        None => return,
+    };
+    c.memory.target_timeout = c.memory.target_timeout.saturating_sub(1);
+    let arrived = target.level == l && level.distance(pos, (target.x, target.y)) <= 1;
+    if arrived || c.memory.target_timeout == 0 {
+        c.memory.target = None;
+        c.memory.path.clear();
+    }
+}
+
+/// Picks something worth going to among what the creature perceives, or an unexplored tile or portal nearby.
+/// The more curious the creature the further it looks.
+pub fn choose_target<const SIZE: usize>(
+    c: &mut Creature,
+    level: &Level<SIZE>,
+    l: usize,
+    pos: (usize, usize),
+    random: &mut FastRandom,
+) {
+    let radius = 4 + c.behavior.curiosity as usize / 16;
+    let mut candidates: Vec<(Candidate, (usize, usize))> = Vec::with_capacity(4);
+
+    if let Some(t) = unexplored_tile(c, level, pos, radius, random) {
+        candidates.push((Candidate::Unexplored, t));
+    }
+    let perceived = |f: fn(&PerceivedKind) -> bool| c.memory.perceived.iter()
+        .filter(|p| f(&p.kind))
+        .map(|p| p.position)
+        .collect::<Vec<_>>();
+    let objects = perceived(|k| matches!(k, PerceivedKind::Object(_)));
+    let creatures = perceived(|k| matches!(k, PerceivedKind::Creature(..)));
+    if !objects.is_empty() {
+        candidates.push((Candidate::Object, objects[random.next_less_than(objects.len() as u64) as usize]));
+    }
+    if !creatures.is_empty() {
+        candidates.push((Candidate::Creature, creatures[random.next_less_than(creatures.len() as u64) as usize]));
+    }
+    if let Some(t) = closest_portal_tile(random, level, pos.0, pos.1, radius, Tile::is_stairs) {
+        candidates.push((Candidate::Portal, t));
+    }
+
+    let total: u64 = candidates.iter().map(|(k, _)| k.weight(c.behavior.curiosity)).sum();
+    if total == 0 {
+        return;
+    }
+    let mut pick = random.next_less_than(total);
+    for (kind, (x, y)) in candidates {
+        let w = kind.weight(c.behavior.curiosity);
+        if pick < w {
+            // Stairs are interesting for where they lead
+            let level_to = match kind {
+                Candidate::Portal => level[(x, y)].tile.stairs_target(l).unwrap_or(l),
+                _ => l,
+            };
+            c.memory.target = Some(SlotPosition { x, y, level: level_to });
+            c.memory.target_timeout = (level.distance(pos, (x, y)) * 4 + 8) as u16;
+            c.memory.path.clear();
+            return;
+        }
+        pick -= w;
+    }
+}
+
+fn unexplored_tile<const SIZE: usize>(
+    c: &Creature,
+    level: &Level<SIZE>,
+    pos: (usize, usize),
+    radius: usize,
+    random: &mut FastRandom,
+) -> Option<(usize, usize)> {
+    for _ in 0..8 {
+        let t = (
+            (pos.0 + SIZE - radius + random.next_less_than(radius as u64 * 2 + 1) as usize) % SIZE,
+            (pos.1 + SIZE - radius + random.next_less_than(radius as u64 * 2 + 1) as usize) % SIZE,
+        );
+        let explored = c.memory.explored.iter().any(|e| level.distance(*e, t) < EXPLORED_RADIUS);
+        if !explored && level[t].tile.is_floor() && level[t].entity.is_none() {
+            return Some(t);
+        }
+    }
+    None
+}
@@ -0,0 +1,77 @@
+use crate::{world::{Level, Entity, ItemMap, creature::{Creature, Diet}}, util::FastRandom};
+
+/// One in how many actions cost a point of nutrition
+const METABOLISM_RATE: u64 = 4;
+
+/// Nutrition gained from a bite of grass
+const GRAZING_NUTRITION: u8 = 24;
+
+/// One in how many actions in full light give light eaters a point of nutrition
+const PHOTOSYNTHESIS_RATE: u64 = 2;
+
+pub fn metabolize(c: &mut Creature, random: &mut FastRandom) {
+    if random.one_in(METABOLISM_RATE) {
+        c.body.nutrition = c.body.nutrition.saturating_sub(1);
+    }
+}
+
+/// Light eaters feed on the light they're standing in, the brighter the likelier
+pub fn photosynthesize(c: &mut Creature, diet: &Diet, light: u8, random: &mut FastRandom) {
+    if diet.light && random.next_less_than(PHOTOSYNTHESIS_RATE * 256) < light as u64 {
+        c.body.nutrition = c.body.nutrition.saturating_add(1).min(c.body.max_nutrition);
+    }
+}
+
+/// Whether there's something at `pos` that a creature with this diet would eat
+pub fn is_edible<const SIZE: usize>(level: &Level<SIZE>, items: &ItemMap, pos: (usize, usize), diet: &Diet) -> bool {
+    let slot = &level[pos];
+    match slot.entity {
+        Some(Entity::Object(o)) => diet.plants && items[&o].is_edible(),
+        _ => diet.plants && slot.tile.grazed(level.open_sky).is_some(),
+    }
+}
+
+/// Plant eaters eat the first edible thing they carry.
+/// Returns whether they had any.
+pub fn eat_carried(c: &mut Creature, items: &ItemMap, diet: &Diet) -> bool {
+    if !diet.plants {
+        return false;
+    }
+    match c.inventory.items.iter().position(|i| items[i].is_edible()) {
+        Some(index) => {
+            let nutrition = items[&c.inventory.items.remove(index)].nutrition;
+            c.body.nutrition = c.body.nutrition.saturating_add(nutrition).min(c.body.max_nutrition);
+            true
+        },
+        None => false,
+    }
+}
+
+/// The creature at `eater` eats the object at `food`, or grazes on it if there's no object.
+/// Returns whether it found anything to eat.
+pub fn eat<const SIZE: usize>(
+    level: &mut Level<SIZE>,
+    items: &ItemMap,
+    eater: (usize, usize),
+    food: (usize, usize),
+) -> bool {
+    let nutrition = match level[food].entity {
+        Some(Entity::Object(o)) => if items[&o].is_edible() {
+            level[food].entity = None;
+            items[&o].nutrition
+        } else {
+            return false
+        },
+        _ => match level[food].tile.grazed(level.open_sky) {
+            Some(grazed) => {
+                level.set_tile(food, grazed);
+                GRAZING_NUTRITION
+            },
+            None => return false,
+        },
+    };
+    if let Some(Entity::Creature(c)) = &mut level[eater].entity {
+        c.body.nutrition = c.body.nutrition.saturating_add(nutrition).min(c.body.max_nutrition);
+    }
+    true
+}
@@ -0,0 +1,47 @@
+use crate::world::{Level, Entity};
+
+/// The creature at `holder` picks up the object at `at` if it has room for it.
+/// Returns whether it did.
+pub fn pick_up<const SIZE: usize>(
+    level: &mut Level<SIZE>,
+    holder: (usize, usize),
+    at: (usize, usize),
+) -> bool {
+    let item = match level[at].entity {
+        Some(Entity::Object(item)) => item,
+        _ => return false,
+    };
+    let picked = match &mut level[holder].entity {
+        Some(Entity::Creature(c)) => c.inventory.add(item),
+        _ => false,
+    };
+    if picked {
+        level[at].entity = None;
+    }
+    picked
+}
+
+/// The creature at `holder` puts down the item at `index` of its inventory on a free neighbour.
+/// Returns whether there was such an item and somewhere to put it.
+pub fn drop_item<const SIZE: usize>(
+    level: &mut Level<SIZE>,
+    holder: (usize, usize),
+    index: usize,
+) -> bool {
+    let to = match level.neighbours(holder).into_iter()
+        .find(|n| level[*n].tile.is_floor() && level[*n].entity.is_none()) {
+        Some(to) => to,
+        None => return false,
+    };
+    let item = match &mut level[holder].entity {
+        Some(Entity::Creature(c)) => c.inventory.remove(index),
+        _ => None,
+    };
+    match item {
+        Some(item) => {
+            level[to].entity = Some(Entity::Object(item));
+            true
+        },
+        None => false,
+    }
+}
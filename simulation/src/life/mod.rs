@@ -1,92 +1,370 @@
-use crate::{world::{World, Entity, creature::Interest, Level, Tile}, util::FastRandom};
+use crate::{world::{World, Entity, ItemID, ItemMap, LevelEvent, SlotPosition, creature::{Interest, Creature, CreatureID, PerceivedKind, SpeciesMap}, Level, Tile}, util::FastRandom};
 
+use self::{
+    action::{Action, Intent, is_alive, resolve_moves, apply_moves},
+    combat::fight,
+    curiosity::{choose_target, remember_position, update_target},
+    feeding::{eat, eat_carried, is_edible, metabolize, photosynthesize},
+    items::pick_up,
+    perception::perceive,
+    relations::{Attitude, closest_with_attitude},
+    reproduction::{closest_mate, is_ready_to_mate, mate},
+    scheduler::{pass_tick, should_scan},
+    sleep::update_sleep,
+    terrain::dig,
+    tracking::{SCENT_ALARM, avoid_scent, follow_scent, leave_scent},
+    vegetation::grow,
+};
+
+pub mod action;
+pub mod combat;
+pub mod curiosity;
+pub mod feeding;
+pub mod items;
+pub mod perception;
+pub mod relations;
+pub mod reproduction;
+pub mod scheduler;
+pub mod sleep;
+pub mod terrain;
+pub mod tracking;
+pub mod vegetation;
 pub mod vision;
 
-pub fn tick<const SIZE: usize, const H: usize>(world: &mut World<SIZE, H>, random: &mut FastRandom) {
+/// How many slots a creature may consider when looking for a path
+const MAX_PATH_EXPANSION: usize = 4096;
+
+/// How far the goal can get from the end of a path before a new one is needed
+const PATH_END_TOLERANCE: usize = 2;
+
+/// Friendly creatures stop following once they're this close
+const FOLLOW_DISTANCE: usize = 3;
+
+/// How far from where it died a creature's things can end up
+const DROP_RADIUS: i64 = 3;
+
+/// Returns what changed in the levels since the last tick, including what happened in between
+pub fn tick<const SIZE: usize, const H: usize>(world: &mut World<SIZE, H>, random: &mut FastRandom) -> Vec<(usize, LevelEvent)> {
+    world.advance_clock();
     let pp = world.player_position;
+    let clock = world.clock;
+    let mut travellers = Vec::new();
     for l in 0..H {
-        let level = &mut world[l];
+        let species = &world.species;
+        let items = &world.items;
+        let level = &mut world.levels[l];
+        let mut intents = Vec::new();
         for x in 0..SIZE {
             for y in 0..SIZE {
+                if pp.x == x && pp.y == y && pp.level == l {
+                    continue;
+                }
                 let pos = (x, y);
-                let slot = &mut level[pos];
-                match &mut slot.entity {
-                    Some(e) => match e {
-                        Entity::Creature(c) => {
-                            if pp.x == x && pp.y == y && pp.level == l {
-                                continue;
-                            }
-                            let interest = c.calculate_interests(random);
-                            match interest {
-                                Interest::Hungry => todo!(),
-                                Interest::Scared => todo!(),
-                                Interest::Curious => match c.memory.target {
-                                    Some(target) => {
-                                        let go_to = if target.level != l {
-                                            closest_portal_tile(random, level, x, y, 128).unwrap_or((target.x, target.y))
-                                        } else {
-                                            (target.x, target.y)
-                                        };
-                                        let direction = (go_to.0 as i64 - x as i64, go_to.1 as i64 - y as i64);
-                                        let direction = if direction.0.abs() > direction.1.abs() {
-                                            (direction.0.signum(), 0)
-                                        } else { (0, direction.1.signum()) };
-                                        let new_pos = (
-                                            (x as i64 + direction.0 + SIZE as i64) as usize % SIZE,
-                                            (y as i64 + direction.1 + SIZE as i64) as usize % SIZE,
-                                        );
-                                        let new_slot = &level[new_pos];
-                                        if new_slot.tile.is_floor() && new_slot.entity.is_none() {
-                                            level.swap_entities(pos, new_pos)
-                                        }
-                                    },
-                                    None => {},
-                                },
-                                Interest::Idle => if random.one_in(16) {
-                                    let d = random.next_less_than(4);
-                                    let new_pos = match d {
-                                        0 => (x, (y + 1) % SIZE),
-                                        1 => (x, (y as i64 - 1 + SIZE as i64) as usize % SIZE),
-                                        2 => ((x + 1) % SIZE, y),
-                                        3 => ((x as i64 - 1 + SIZE as i64) as usize % SIZE, y),
-                                        _ => panic!("Random number generator is busted apparently")
-                                    };
-                                    let new_slot = &level[new_pos];
-                                    if new_slot.tile.is_floor() && new_slot.entity.is_none() {
-                                        level.swap_entities(pos, new_pos)
-                                    }
-                                },
-                            }
-                        },
-                        _ => {},
+                let mut c = match level[pos].entity.take() {
+                    Some(Entity::Creature(c)) => c,
+                    e => {
+                        level[pos].entity = e;
+                        continue;
                     },
-                    None => {},
+                };
+                if pass_tick(&mut c) {
+                    metabolize(&mut c, random);
+                    let template = &species[&c.species];
+                    photosynthesize(&mut c, &template.diet, level[pos].light, random);
+                    if update_sleep(&mut c, template.nocturnal, &clock) {
+                        level[pos].entity = Some(Entity::Creature(c));
+                        continue;
+                    }
+                    let action = decide(&mut c, level, species, items, l, pos, random);
+                    if let Action::Move(to) = action {
+                        let (dx, dy) = level.offset(pos, to);
+                        c.memory.facing = (dx.signum() as i8, dy.signum() as i8);
+                    }
+                    intents.push(Intent { from: pos, action });
                 }
+                level[pos].entity = Some(Entity::Creature(c));
+            }
+        }
+        for intent in &intents {
+            // Killed by someone who went first
+            if !is_alive(level, intent.from) {
+                continue;
             }
+            match intent.action {
+                Action::Attack(to) => { fight(level, species, random, intent.from, to); },
+                Action::Eat(food) => { eat(level, items, intent.from, food); },
+                Action::PickUp(at) => { pick_up(level, intent.from, at); },
+                Action::Dig(at) => { dig(level, items, intent.from, at); },
+                Action::TakeStairs => travellers.push(SlotPosition { x: intent.from.0, y: intent.from.1, level: l }),
+                _ => {},
+            }
+        }
+        let moves = resolve_moves(level, &intents);
+        apply_moves(level, &moves);
+        for intent in &intents {
+            match intent.action {
+                Action::Mate(with) if is_alive(level, intent.from) => { mate(level, random, intent.from, with); },
+                _ => {},
+            }
+        }
+        grow(level, items, random);
+        if level.light_dirty {
+            level.update_light();
         }
     }
+    // Only once every level is done, so nobody gets to act twice
+    for from in travellers {
+        if let Some(arrival) = world.take_portal(&from) {
+            // What it knew about is on the level it left, so it has to look around again
+            if let Some(Entity::Creature(c)) = &mut world[&arrival].entity {
+                c.memory.path.clear();
+                c.memory.perceived.clear();
+                c.memory.explored.clear();
+                c.memory.scan_cooldown = 0;
+            }
+        }
+    }
+    world.flow_water(random);
     for l in 0..H {
-        let level = &mut world[l];
+        let species = &world.species;
+        let level = &mut world.levels[l];
+        level.diffuse_scent();
         for x in 0..SIZE {
             for y in 0..SIZE {
+                if let Some(Entity::Creature(c)) = &level[(x, y)].entity {
+                    let (smelliness, diet) = (c.body.smelliness, species[&c.species].diet);
+                    leave_scent(level, (x, y), smelliness, &diet);
+                }
                 let slot = &mut level[(x, y)];
-                match &mut slot.entity {
-                    Some(e) => match e {
-                        Entity::Creature(c) => {
-                            if c.body.health == 0 {
-                                println!("hungy??? {:?}", c);
-                                todo!("entity death")
-                            } else if c.body.nutrition == 0 {
-                                c.body.health -= 1;
-                            }
-                        },
-                        _ => {},
-                    },
-                    None => {},
+                if let Some(Entity::Creature(c)) = &mut slot.entity {
+                    if c.body.health == 0 {
+                        // The player's death is for the game to handle
+                        if !(pp.x == x && pp.y == y && pp.level == l) {
+                            let items = std::mem::take(&mut c.inventory.items);
+                            slot.entity = None;
+                            drop_items(level, (x, y), items);
+                        }
+                    } else if c.body.nutrition == 0 {
+                        c.body.health -= 1;
+                    }
                 }
             }
         }
     }
+    world.take_events()
+}
+
+fn decide<const SIZE: usize>(
+    c: &mut Creature,
+    level: &Level<SIZE>,
+    species: &SpeciesMap,
+    items: &ItemMap,
+    l: usize,
+    (x, y): (usize, usize),
+    random: &mut FastRandom,
+) -> Action {
+    remember_position(c, (x, y));
+    update_target(c, level, l, (x, y));
+    let interest = if should_scan(c) {
+        perceive(c, level, (x, y));
+        c.calculate_interests(random)
+    } else {
+        c.memory.current_interest
+    };
+    if !matches!(interest, Interest::Scared) {
+        if let Some((enemy, id)) = closest_with_attitude(c, species, Attitude::Hostile) {
+            return if level.distance((x, y), enemy) == 1 && is_creature(level, enemy, id) {
+                Action::Attack(enemy)
+            } else {
+                step_towards(c, level, (x, y), enemy)
+            };
+        }
+    }
+    if matches!(interest, Interest::Idle | Interest::Curious) && is_ready_to_mate(c) {
+        if let Some(partner) = closest_mate(c) {
+            return if level.distance((x, y), partner) == 1 {
+                Action::Mate(partner)
+            } else {
+                step_towards(c, level, (x, y), partner)
+            };
+        }
+    }
+    let diet = species[&c.species].diet;
+    if !diet.meat && matches!(interest, Interest::Idle | Interest::Curious) && level[(x, y)].scent.predator >= SCENT_ALARM {
+        if let Action::Move(to) = avoid_scent(level, (x, y), |s| s.predator) {
+            return Action::Move(to);
+        }
+    }
+    match interest {
+        Interest::Hungry => {
+            if eat_carried(c, items, &diet) {
+                return Action::Wait;
+            }
+            if is_edible(level, items, (x, y), &diet) {
+                return Action::Eat((x, y));
+            }
+            match closest_food(c, species, items) {
+                Some(food) if level.distance((x, y), food) == 1 && matches!(level[food].entity, Some(Entity::Creature(_))) => Action::Attack(food),
+                Some(food) if level.distance((x, y), food) == 1 && is_edible(level, items, food, &diet) => Action::Eat(food),
+                Some(food) => step_towards(c, level, (x, y), food),
+                None => match if diet.meat { follow_scent(level, (x, y), |s| s.prey) } else { Action::Wait } {
+                    Action::Wait if diet.light => towards_light(level, (x, y)),
+                    Action::Wait => wander(random, (x, y), SIZE),
+                    trail => trail,
+                },
+            }
+        },
+        Interest::Scared => match closest_creature(c) {
+            Some(threat) => flee(level, (x, y), threat),
+            None => avoid_scent(level, (x, y), |s| s.predator),
+        },
+        Interest::Curious => match c.memory.target.or_else(|| {
+            choose_target(c, level, l, (x, y), random);
+            c.memory.target
+        }) {
+            Some(target) => {
+                let go_to = if target.level != l {
+                    let stairs = if target.level > l { Tile::StairsDown } else { Tile::StairsUp };
+                    if level[(x, y)].tile == stairs {
+                        return Action::TakeStairs;
+                    }
+                    closest_portal_tile(random, level, x, y, 128, |t| *t == stairs).unwrap_or((target.x, target.y))
+                } else {
+                    (target.x, target.y)
+                };
+                match step_towards(c, level, (x, y), go_to) {
+                    Action::Wait if species[&c.species].can_dig => dig_towards(level, (x, y), go_to),
+                    action => action,
+                }
+            },
+            None => Action::Wait,
+        },
+        Interest::Idle => match closest_with_attitude(c, species, Attitude::Friendly) {
+            Some((friend, _)) if level.distance((x, y), friend) > FOLLOW_DISTANCE => step_towards(c, level, (x, y), friend),
+            // Fed creatures store away the food lying next to them for later
+            _ => if let Some(food) = closest_food(c, species, items).filter(|food|
+                !c.inventory.is_full() && level.distance((x, y), *food) == 1 &&
+                matches!(level[*food].entity, Some(Entity::Object(_))) && is_edible(level, items, *food, &diet)
+            ) {
+                Action::PickUp(food)
+            } else if random.one_in(16) {
+                wander(random, (x, y), SIZE)
+            } else { Action::Wait },
+        },
+    }
+}
+
+/// Whatever it carried stays behind, where it died and on the closest free floor around
+fn drop_items<const SIZE: usize>(level: &mut Level<SIZE>, (x, y): (usize, usize), items: Vec<ItemID>) {
+    let mut items = items.into_iter();
+    for dist in 0..=DROP_RADIUS {
+        for dx in -dist..=dist {
+            for dy in -dist..=dist {
+                if dx.abs() != dist && dy.abs() != dist {
+                    continue;
+                }
+                let p = (
+                    (x as i64 + dx + SIZE as i64) as usize % SIZE,
+                    (y as i64 + dy + SIZE as i64) as usize % SIZE,
+                );
+                if !level[p].tile.is_floor() || level[p].entity.is_some() {
+                    continue;
+                }
+                match items.next() {
+                    Some(item) => level[p].entity = Some(Entity::Object(item)),
+                    None => return,
+                }
+            }
+        }
+    }
+}
+
+fn is_creature<const SIZE: usize>(level: &Level<SIZE>, pos: (usize, usize), id: CreatureID) -> bool {
+    matches!(&level[pos].entity, Some(Entity::Creature(c)) if c.id == id)
+}
+
+fn wander(random: &mut FastRandom, (x, y): (usize, usize), size: usize) -> Action {
+    let d = random.next_less_than(4);
+    Action::Move(match d {
+        0 => (x, (y + 1) % size),
+        1 => (x, (y as i64 - 1 + size as i64) as usize % size),
+        2 => ((x + 1) % size, y),
+        3 => ((x as i64 - 1 + size as i64) as usize % size, y),
+        _ => panic!("Random number generator is busted apparently")
+    })
+}
+
+/// Plants for herbivores, creatures of other species for carnivores
+fn closest_food(c: &Creature, species: &SpeciesMap, items: &ItemMap) -> Option<(usize, usize)> {
+    let diet = species[&c.species].diet;
+    c.memory.perceived.iter().find(|p| match p.kind {
+        PerceivedKind::Plant => diet.plants,
+        PerceivedKind::Creature(s, _) => diet.meat && s != c.species,
+        PerceivedKind::Object(o) => diet.plants && items[&o].is_edible(),
+    }).map(|p| p.position)
+}
+
+fn closest_creature(c: &Creature) -> Option<(usize, usize)> {
+    c.memory.perceived.iter()
+        .find(|p| matches!(p.kind, PerceivedKind::Creature(..)))
+        .map(|p| p.position)
+}
+
+/// Steps to whichever neighbour is the furthest from the threat, if any is further than where we are
+fn flee<const SIZE: usize>(
+    level: &Level<SIZE>,
+    pos: (usize, usize),
+    threat: (usize, usize),
+) -> Action {
+    let current = level.distance(pos, threat);
+    level.neighbours(pos).into_iter()
+        .filter(|n| level.is_passable(*n, false) && level[*n].entity.is_none())
+        .map(|n| (level.distance(n, threat), n))
+        .filter(|(d, _)| *d > current)
+        .max_by_key(|(d, _)| *d)
+        .map_or(Action::Wait, |(_, n)| Action::Move(n))
+}
+
+/// Steps to the brightest neighbour, if any is brighter than where we are
+fn towards_light<const SIZE: usize>(level: &Level<SIZE>, pos: (usize, usize)) -> Action {
+    level.neighbours(pos).into_iter()
+        .filter(|n| level.is_passable(*n, false) && level[*n].entity.is_none() && level[*n].light > level[pos].light)
+        .max_by_key(|n| level[*n].light)
+        .map_or(Action::Wait, Action::Move)
+}
+
+/// Digs into whichever wall next to it gets closest to `go_to`, for when there's no way around
+fn dig_towards<const SIZE: usize>(level: &Level<SIZE>, pos: (usize, usize), go_to: (usize, usize)) -> Action {
+    level.neighbours(pos).into_iter()
+        .filter(|n| !level[*n].tile.is_floor() && level[*n].tile.dug().is_some() && level[*n].entity.is_none())
+        .min_by_key(|n| level.distance(*n, go_to))
+        .filter(|n| level.distance(*n, go_to) < level.distance(pos, go_to))
+        .map_or(Action::Wait, Action::Dig)
+}
+
+/// Follows the path in memory, finding a new one when there is none or it doesn't lead close to `go_to` anymore.
+/// Moving goals only cause a new search once they've wandered off from the end of the path.
+fn step_towards<const SIZE: usize>(
+    c: &mut Creature,
+    level: &Level<SIZE>,
+    pos: (usize, usize),
+    go_to: (usize, usize),
+) -> Action {
+    let path = &mut c.memory.path;
+    while path.last() == Some(&pos) {
+        path.pop();
+    }
+    let outdated = match (path.first(), path.last()) {
+        (Some(&end), Some(&next)) => level.distance(end, go_to) > PATH_END_TOLERANCE || level.distance(pos, next) != 1 || !level.is_passable(next, false),
+        _ => true,
+    };
+    if outdated {
+        *path = level.find_path(pos, go_to, false, MAX_PATH_EXPANSION).unwrap_or_default();
+    }
+    match path.last() {
+        Some(&next) => Action::Move(next),
+        None => Action::Wait,
+    }
 }
 
 fn closest_portal_tile<const SIZE: usize>(
@@ -95,6 +373,7 @@ fn closest_portal_tile<const SIZE: usize>(
     x: usize,
     y: usize,
     max_axis_distance: usize,
+    wanted: impl Fn(&Tile) -> bool,
 ) -> Option<(usize, usize)> {
     let mut dist = 1;
     'l: loop {
@@ -103,21 +382,21 @@ fn closest_portal_tile<const SIZE: usize>(
         }
         for x in (x as i64 - dist)..=(x as i64 + dist) {
             let c = ((x + SIZE as i64) as usize % SIZE, (y as i64 - dist + SIZE as i64) as usize % SIZE);
-            if matches!(level[c].tile, Tile::GotoUnderworld | Tile::GotoOverworld) {
+            if wanted(&level[c].tile) {
                 break 'l Some(c);
             }
             let c = ((x + SIZE as i64) as usize % SIZE, (y as i64 + dist) as usize % SIZE);
-            if matches!(level[c].tile, Tile::GotoUnderworld | Tile::GotoOverworld) {
+            if wanted(&level[c].tile) {
                 break 'l Some(c);
             }
         }
         for y in (y as i64 - dist + 1)..(y as i64 + dist) {
             let c = ((x as i64 - dist + SIZE as i64) as usize % SIZE, (y + SIZE as i64) as usize % SIZE);
-            if matches!(level[c].tile, Tile::GotoUnderworld | Tile::GotoOverworld) {
+            if wanted(&level[c].tile) {
                 break 'l Some(c);
             }
             let c = ((x as i64 + dist) as usize % SIZE, (y + SIZE as i64) as usize % SIZE);
-            if matches!(level[c].tile, Tile::GotoUnderworld | Tile::GotoOverworld) {
+            if wanted(&level[c].tile) {
                 break 'l Some(c);
             }
         }
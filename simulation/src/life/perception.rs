@@ -0,0 +1,76 @@
+use crate::world::{Level, Entity, creature::{Creature, Perceived, PerceivedKind, Sense}};
+
+use super::vision::can_see;
+
+/// The smelliest creatures can be noticed from this far
+const MAX_SMELL_RANGE: usize = 8;
+
+/// Less aware creatures see about as far as the end of their nose
+pub fn sight_radius(awareness: u8) -> usize {
+    3 + awareness as usize / 32
+}
+
+pub fn smell_range(smelliness: u8) -> usize {
+    smelliness as usize * MAX_SMELL_RANGE / 255
+}
+
+/// Aware creatures keep an eye on their back, the others only see what's in front of them
+pub fn in_field_of_view(facing: (i8, i8), awareness: u8, offset: (i64, i64)) -> bool {
+    if facing == (0, 0) {
+        return true;
+    }
+    let dot = (facing.0 as i64 * offset.0 + facing.1 as i64 * offset.1) as f64;
+    let length = ((offset.0 * offset.0 + offset.1 * offset.1) as f64).sqrt();
+    // From 90° at no awareness up to 270°
+    let half_angle = std::f64::consts::FRAC_PI_4 * (1.0 + 2.0 * awareness as f64 / 255.0);
+    dot >= length * half_angle.cos()
+}
+
+/// Fills the creature's memory with the creatures and objects it sees or smells, and the closest plant in sight
+pub fn perceive<const SIZE: usize>(
+    c: &mut Creature,
+    level: &Level<SIZE>,
+    pos: (usize, usize),
+) {
+    let sight = sight_radius(c.behavior.awareness);
+    let radius = sight.max(MAX_SMELL_RANGE) as i64;
+    let seen = |xoff: i64, yoff: i64| in_field_of_view(c.memory.facing, c.behavior.awareness, (xoff, yoff)) &&
+        can_see(level, pos.0, pos.1, xoff, yoff, sight);
+
+    let mut perceived = Vec::new();
+    let mut plant: Option<(usize, (usize, usize))> = None;
+    for xoff in -radius..=radius {
+        for yoff in -radius..=radius {
+            if xoff == 0 && yoff == 0 {
+                continue;
+            }
+            let p = (
+                (pos.0 as i64 + xoff).rem_euclid(SIZE as i64) as usize,
+                (pos.1 as i64 + yoff).rem_euclid(SIZE as i64) as usize,
+            );
+            let distance = level.distance(pos, p);
+            let slot = &level[p];
+            match &slot.entity {
+                Some(Entity::Creature(other)) => {
+                    let sense = if seen(xoff, yoff) {
+                        Sense::Sight
+                    } else if distance <= smell_range(other.body.smelliness) {
+                        Sense::Smell
+                    } else { continue };
+                    perceived.push((distance, Perceived { position: p, kind: PerceivedKind::Creature(other.species, other.id), sense }));
+                },
+                Some(Entity::Object(o)) => if seen(xoff, yoff) {
+                    perceived.push((distance, Perceived { position: p, kind: PerceivedKind::Object(*o), sense: Sense::Sight }));
+                },
+                None => if slot.tile.is_floor() && slot.tile.is_grassy() && plant.is_none_or(|(d, _)| distance < d) && seen(xoff, yoff) {
+                    plant = Some((distance, p));
+                },
+            }
+        }
+    }
+    if let Some((distance, p)) = plant {
+        perceived.push((distance, Perceived { position: p, kind: PerceivedKind::Plant, sense: Sense::Sight }));
+    }
+    perceived.sort_by_key(|(d, _)| *d);
+    c.memory.perceived = perceived.into_iter().map(|(_, p)| p).collect();
+}
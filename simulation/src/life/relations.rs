@@ -0,0 +1,64 @@
+use crate::world::creature::{Creature, CreatureID, SpeciesID, SpeciesMap, PerceivedKind};
+
+/// How many attackers a creature holds a grudge against at once
+const MAX_GRUDGES: usize = 8;
+
+/// Creatures of the same species get along a bit better
+const SAME_SPECIES_BONUS: i16 = 32;
+
+/// How far from neutral the opinion has to be to act on it
+const ATTITUDE_THRESHOLD: i16 = 32;
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum Attitude {
+    Friendly,
+    Neutral,
+    Hostile,
+}
+
+/// What `c` thinks of another creature, based on its own friendliness,
+/// what its species thinks of the other's, and whether it has been attacked by it before
+pub fn attitude(
+    c: &Creature,
+    other_species: SpeciesID,
+    other_id: CreatureID,
+    species: &SpeciesMap,
+) -> Attitude {
+    if c.memory.grudges.contains(&other_id) {
+        return Attitude::Hostile;
+    }
+    let mut opinion = c.behavior.friendliness as i16;
+    if other_species == c.species {
+        opinion += SAME_SPECIES_BONUS;
+    } else if let Some(&relation) = species[&c.species].relations.get(&species[&other_species].name) {
+        opinion += relation as i16;
+    }
+    if opinion >= ATTITUDE_THRESHOLD {
+        Attitude::Friendly
+    } else if opinion <= -ATTITUDE_THRESHOLD {
+        Attitude::Hostile
+    } else {
+        Attitude::Neutral
+    }
+}
+
+pub fn hold_grudge(c: &mut Creature, attacker: CreatureID) {
+    let grudges = &mut c.memory.grudges;
+    grudges.retain(|g| *g != attacker);
+    if grudges.len() >= MAX_GRUDGES {
+        grudges.remove(0);
+    }
+    grudges.push(attacker);
+}
+
+/// The closest perceived creature `c` has the given attitude towards
+pub fn closest_with_attitude(
+    c: &Creature,
+    species: &SpeciesMap,
+    wanted: Attitude,
+) -> Option<((usize, usize), CreatureID)> {
+    c.memory.perceived.iter().find_map(|p| match p.kind {
+        PerceivedKind::Creature(s, id) if attitude(c, s, id, species) == wanted => Some((p.position, id)),
+        _ => None,
+    })
+}
@@ -0,0 +1,52 @@
+use crate::{world::{Level, Entity, creature::{Creature, PerceivedKind, offspring}}, util::FastRandom};
+
+/// Ticks a creature has to wait between two offspring
+const MATING_COOLDOWN: u16 = 256;
+
+/// Well fed and rested
+pub fn is_ready_to_mate(c: &Creature) -> bool {
+    c.memory.mating_cooldown == 0 &&
+        c.body.nutrition as usize >= c.body.max_nutrition as usize * 9 / 10 &&
+        c.body.health as usize >= c.body.max_health as usize / 2
+}
+
+/// The closest perceived creature of the same species, whether or not it's in the mood
+pub fn closest_mate(c: &Creature) -> Option<(usize, usize)> {
+    c.memory.perceived.iter().find_map(|p| match p.kind {
+        PerceivedKind::Creature(s, _) if s == c.species => Some(p.position),
+        _ => None,
+    })
+}
+
+/// The creatures at `a` and `b` have offspring next to them if both are ready and there's room for it.
+/// Having it costs each parent a quarter of its nutrition. Returns whether it happened.
+pub fn mate<const SIZE: usize>(
+    level: &mut Level<SIZE>,
+    random: &mut FastRandom,
+    a: (usize, usize),
+    b: (usize, usize),
+) -> bool {
+    if level.distance(a, b) != 1 {
+        return false;
+    }
+    let child = match (&level[a].entity, &level[b].entity) {
+        (Some(Entity::Creature(ca)), Some(Entity::Creature(cb)))
+            if ca.species == cb.species && is_ready_to_mate(ca) && is_ready_to_mate(cb) => offspring(random, ca, cb),
+        _ => return false,
+    };
+    let nursery = level.neighbours(a).into_iter()
+        .chain(level.neighbours(b))
+        .find(|n| level[*n].tile.is_floor() && level[*n].entity.is_none());
+    let nursery = match nursery {
+        Some(n) => n,
+        None => return false,
+    };
+    level[nursery].entity = Some(Entity::Creature(child));
+    for parent in [a, b] {
+        if let Some(Entity::Creature(c)) = &mut level[parent].entity {
+            c.body.nutrition -= c.body.max_nutrition / 4;
+            c.memory.mating_cooldown = MATING_COOLDOWN;
+        }
+    }
+    true
+}
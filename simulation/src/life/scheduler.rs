@@ -0,0 +1,32 @@
+use crate::world::creature::Creature;
+
+/// Energy spent on a single action, a creature with full speed acts every tick
+pub const ACTION_COST: u16 = 256;
+
+/// Ticks between two scans for a creature with no awareness at all
+const MAX_SCAN_INTERVAL: u8 = 8;
+
+/// Lets one tick pass for the creature: it gains energy and its cooldowns go down.
+/// Returns whether it has enough energy to act, in which case the cost is already paid.
+pub fn pass_tick(c: &mut Creature) -> bool {
+    c.memory.scan_cooldown = c.memory.scan_cooldown.saturating_sub(1);
+    c.memory.mating_cooldown = c.memory.mating_cooldown.saturating_sub(1);
+    c.body.energy = c.body.energy.saturating_add(c.body.speed as u16 + 1);
+    if c.body.energy >= ACTION_COST {
+        c.body.energy -= ACTION_COST;
+        true
+    } else { false }
+}
+
+/// Should only be called when the creature acts, returns whether it looks around this time
+pub fn should_scan(c: &mut Creature) -> bool {
+    if c.memory.scan_cooldown == 0 {
+        c.memory.scan_cooldown = scan_interval(c.behavior.awareness);
+        true
+    } else { false }
+}
+
+/// More aware creatures scan every tick, the least aware ones every `MAX_SCAN_INTERVAL` ticks
+pub fn scan_interval(awareness: u8) -> u8 {
+    MAX_SCAN_INTERVAL - (awareness as u16 * (MAX_SCAN_INTERVAL as u16 - 1) / 255) as u8
+}
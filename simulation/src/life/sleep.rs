@@ -0,0 +1,22 @@
+use crate::world::{Clock, creature::{Creature, Interest}};
+
+/// Whether it's the time of day this kind of creature sleeps
+pub fn is_rest_time(nocturnal: bool, clock: &Clock) -> bool {
+    clock.is_night() != nocturnal
+}
+
+/// Creatures fall asleep during their rest time unless they're hungry or scared,
+/// and wake up once it's over or hunger gets to them.
+/// Returns whether the creature is asleep.
+pub fn update_sleep(c: &mut Creature, nocturnal: bool, clock: &Clock) -> bool {
+    let resting = is_rest_time(nocturnal, clock);
+    let starving = c.body.nutrition < c.body.max_nutrition / 4;
+    if c.memory.asleep {
+        c.memory.asleep = resting && !starving;
+    } else if resting && !starving && !matches!(c.memory.current_interest, Interest::Hungry | Interest::Scared) {
+        c.memory.asleep = true;
+        c.memory.target = None;
+        c.memory.path.clear();
+    }
+    c.memory.asleep
+}
@@ -0,0 +1,58 @@
+use crate::world::{Level, Entity, ItemMap, Tile, find_item};
+
+/// What digging leaves behind and building uses up
+const BUILDING_MATERIAL: &str = "stone";
+
+/// The creature at `digger` digs through the tile at `at`, which leaves a stone behind.
+/// The stone goes straight into its inventory if there's room for it.
+/// Returns whether there was anything to dig.
+pub fn dig<const SIZE: usize>(
+    level: &mut Level<SIZE>,
+    items: &ItemMap,
+    digger: (usize, usize),
+    at: (usize, usize),
+) -> bool {
+    let dug = match level[at].tile.dug() {
+        Some(dug) if level[at].entity.is_none() => dug,
+        _ => return false,
+    };
+    level.set_tile(at, dug);
+    if let Some(stone) = find_item(items, BUILDING_MATERIAL) {
+        let carried = match &mut level[digger].entity {
+            Some(Entity::Creature(c)) => c.inventory.add(stone),
+            _ => false,
+        };
+        if !carried {
+            level[at].entity = Some(Entity::Object(stone));
+        }
+    }
+    true
+}
+
+/// The creature at `builder` walls off the free slot at `at` with a stone it carries.
+/// Returns whether it could.
+pub fn build<const SIZE: usize>(
+    level: &mut Level<SIZE>,
+    items: &ItemMap,
+    builder: (usize, usize),
+    at: (usize, usize),
+) -> bool {
+    if !level[at].tile.is_buildable() || level[at].entity.is_some() {
+        return false;
+    }
+    let stone = match find_item(items, BUILDING_MATERIAL) {
+        Some(stone) => stone,
+        None => return false,
+    };
+    let used = match &mut level[builder].entity {
+        Some(Entity::Creature(c)) => match c.inventory.items.iter().position(|i| *i == stone) {
+            Some(index) => c.inventory.remove(index).is_some(),
+            None => false,
+        },
+        _ => false,
+    };
+    if used {
+        level.set_tile(at, Tile::StoneWall);
+    }
+    used
+}
@@ -0,0 +1,43 @@
+use crate::world::{Level, Scent, creature::Diet};
+
+use super::action::Action;
+
+/// Predator scent at which plant eaters start to get away from it
+pub const SCENT_ALARM: u8 = 8;
+
+/// Smellier creatures leave a stronger trail, on the channel matching their diet
+pub fn leave_scent<const SIZE: usize>(level: &mut Level<SIZE>, pos: (usize, usize), smelliness: u8, diet: &Diet) {
+    let scent = &mut level[pos].scent;
+    let amount = smelliness / 4;
+    if diet.meat {
+        scent.predator = scent.predator.saturating_add(amount);
+    } else {
+        scent.prey = scent.prey.saturating_add(amount);
+    }
+}
+
+/// Steps to the free neighbour where the scent is strongest, if it's stronger than here
+pub fn follow_scent<const SIZE: usize>(
+    level: &Level<SIZE>,
+    pos: (usize, usize),
+    channel: fn(&Scent) -> u8,
+) -> Action {
+    let here = channel(&level[pos].scent);
+    level.neighbours(pos).into_iter()
+        .filter(|n| level.is_passable(*n, false) && level[*n].entity.is_none() && channel(&level[*n].scent) > here)
+        .max_by_key(|n| channel(&level[*n].scent))
+        .map_or(Action::Wait, Action::Move)
+}
+
+/// Steps to the free neighbour where the scent is weakest, if it's weaker than here
+pub fn avoid_scent<const SIZE: usize>(
+    level: &Level<SIZE>,
+    pos: (usize, usize),
+    channel: fn(&Scent) -> u8,
+) -> Action {
+    let here = channel(&level[pos].scent);
+    level.neighbours(pos).into_iter()
+        .filter(|n| level.is_passable(*n, false) && level[*n].entity.is_none() && channel(&level[*n].scent) < here)
+        .min_by_key(|n| channel(&level[*n].scent))
+        .map_or(Action::Wait, Action::Move)
+}
@@ -0,0 +1,43 @@
+use crate::{world::{Level, Entity, ItemID, ItemMap, find_item}, util::FastRandom};
+
+/// One in how many slots get a chance to grow every tick
+const GROWTH_SPARSENESS: usize = 64;
+
+/// One in how many times a fruit tree that gets to grow drops a fruit
+const FRUIT_CHANCE: u64 = 8;
+
+/// Picks random slots and lets their plants grow, the way grass spreads slowly rather than all at once.
+/// Grazed tiles grow back faster the more humid they are, and fruit trees drop fruits around them.
+pub fn grow<const SIZE: usize>(level: &mut Level<SIZE>, items: &ItemMap, random: &mut FastRandom) {
+    for _ in 0..(SIZE * SIZE / GROWTH_SPARSENESS) {
+        let pos = (random.next_less_than(SIZE as u64) as usize, random.next_less_than(SIZE as u64) as usize);
+        let slot = &level[pos];
+        match slot.tile.fruit().and_then(|name| find_item(items, name)) {
+            Some(fruit) => if random.one_in(FRUIT_CHANCE) {
+                drop_fruit(level, random, pos, fruit);
+            },
+            None => if let Some(regrown) = slot.tile.regrown(slot.humidity, level.open_sky) {
+                if random.next_less_than(256) < slot.humidity as u64 / 8 {
+                    level.set_tile(pos, regrown);
+                }
+            },
+        }
+    }
+}
+
+/// Puts a fruit on a free neighbour, unless the tree already has one lying around
+fn drop_fruit<const SIZE: usize>(
+    level: &mut Level<SIZE>,
+    random: &mut FastRandom,
+    tree: (usize, usize),
+    fruit: ItemID,
+) {
+    let neighbours = level.neighbours(tree);
+    if neighbours.iter().any(|n| matches!(level[*n].entity, Some(Entity::Object(o)) if o == fruit)) {
+        return;
+    }
+    let n = neighbours[random.next_less_than(4) as usize];
+    if level[n].tile.is_floor() && level[n].entity.is_none() {
+        level[n].entity = Some(Entity::Object(fruit));
+    }
+}
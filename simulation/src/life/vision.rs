@@ -1,5 +1,8 @@
 use crate::world::Level;
 
+/// How far one can make things out in complete darkness
+const DARK_VISION_RADIUS: usize = 6;
+
 pub fn look<const SIZE: usize>(
     level: &Level<SIZE>,
     x: usize,
@@ -30,4 +33,57 @@ pub fn look<const SIZE: usize>(
         }
     }
     vision
-}
\ No newline at end of file
+}
+
+/// Like `look`, but visible slots are as bright as the light falling on them.
+/// In the dark, whatever is within `DARK_VISION_RADIUS` can still be made out, fading with distance.
+pub fn look_lit<const SIZE: usize>(
+    level: &Level<SIZE>,
+    x: usize,
+    y: usize,
+    radius: usize,
+) -> Vec<f32> {
+    let mut vision = look(level, x, y, radius);
+    let vsize = radius * 2 + 1;
+    let dark_radius2 = (DARK_VISION_RADIUS * DARK_VISION_RADIUS) as f32;
+    for xoff in -(radius as i64)..=radius as _ {
+        for yoff in -(radius as i64)..=radius as _ {
+            let vi = (xoff + radius as i64) as usize * vsize + (yoff + radius as i64) as usize;
+            if vision[vi] == 0.0 {
+                continue;
+            }
+            let vx = (x as i64 + xoff).rem_euclid(SIZE as i64) as usize;
+            let vy = (y as i64 + yoff).rem_euclid(SIZE as i64) as usize;
+            let light = level[(vx, vy)].light as f32 / 255.0;
+            let dark_vision = 1.0 - (xoff * xoff + yoff * yoff) as f32 / dark_radius2;
+            vision[vi] = light.max(dark_vision);
+        }
+    }
+    vision
+}
+
+/// Whether the slot at the given offset can be seen from (x, y), that is if it's within `radius`
+/// and there's nothing opaque on the way. Cheaper than `look` when only a few slots matter.
+pub fn can_see<const SIZE: usize>(
+    level: &Level<SIZE>,
+    x: usize,
+    y: usize,
+    xoff: i64,
+    yoff: i64,
+    radius: usize,
+) -> bool {
+    let dist2 = xoff * xoff + yoff * yoff;
+    if dist2 as usize >= radius * radius {
+        return false;
+    }
+    let steps = xoff.abs().max(yoff.abs()) * 2;
+    for i in 0..=steps {
+        let i = i as f64 / steps as f64;
+        let vx = (x as i64 + (xoff as f64 * i) as i64).rem_euclid(SIZE as i64) as usize;
+        let vy = (y as i64 + (yoff as f64 * i) as i64).rem_euclid(SIZE as i64) as usize;
+        if level[(vx, vy)].tile.is_opaque() {
+            return false;
+        }
+    }
+    true
+}
@@ -12,8 +12,9 @@ impl FastRandom {
         b ^ (b << 4)
     }
 
+    /// A zero seed would only ever give zeros, so it's bumped to 1
     #[inline(always)]
-    pub fn new(seed: u64) -> Self { Self { seed } }
+    pub fn new(seed: u64) -> Self { Self { seed: seed.max(1) } }
 
     #[inline(always)]
     pub fn new_from_sys_time() -> Self { Self { seed: SystemTime::UNIX_EPOCH.elapsed().unwrap().as_secs() } }
@@ -1,11 +1,13 @@
 use std::fmt::Display;
 
-use super::{World, SlotPosition, Level};
+use super::{World, SlotPosition, Level, Tile, RoomKind};
 
 pub enum Biome {
     GrassyArea(WaterCloseness),
     Forest(WaterCloseness),
     PlainCave(WaterCloseness),
+    Beach,
+    Snowfield(WaterCloseness),
     Sea,
 }
 
@@ -20,6 +22,8 @@ impl Display for Biome {
             Biome::GrassyArea(w) => write!(f, "grassy area, {}", w),
             Biome::Forest(w) => write!(f, "forest, {}", w),
             Biome::PlainCave(w) => write!(f, "plain cave, {}", w),
+            Biome::Beach => write!(f, "beach"),
+            Biome::Snowfield(w) => write!(f, "snowfield, {}", w),
             Biome::Sea => write!(f, "sea"),
         }
     }
@@ -36,11 +40,16 @@ impl Display for WaterCloseness {
 
 pub struct Ambient {
     pub biome: Biome,
+    /// What kind of dungeon room one's in, if any
+    pub room: Option<RoomKind>,
 }
 
 impl Display for Ambient {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        write!(f, "{}", self.biome)
+        match self.room {
+            Some(room) => write!(f, "{}, in a {}", self.biome, room),
+            None => write!(f, "{}", self.biome),
+        }
     }
 }
 
@@ -66,6 +75,8 @@ impl<const SIZE: usize> Level<SIZE> {
         let mut grassy_count = 0;
         let mut stony_count = 0;
         let mut tree_count = 0;
+        let mut sand_count = 0;
+        let mut snow_count = 0;
         for x in (x as i64 - radius as i64)..=(x + radius) as i64 {
             for y in (y as i64 - radius as i64)..=(y + radius) as i64 {
                 let slot = &self[((x + SIZE as i64) as usize % SIZE, (y + SIZE as i64) as usize % SIZE)];
@@ -78,6 +89,12 @@ impl<const SIZE: usize> Level<SIZE> {
                 else if slot.tile.is_stony() {
                     stony_count += 1;
                 }
+                else if matches!(slot.tile, Tile::Sand | Tile::PalmTree) {
+                    sand_count += 1;
+                }
+                else if matches!(slot.tile, Tile::Snow | Tile::SpruceTree) {
+                    snow_count += 1;
+                }
                 else if slot.tile.is_swimmable() {
                     swimmable_count += 1;
                 }
@@ -93,7 +110,11 @@ impl<const SIZE: usize> Level<SIZE> {
             } else {
                 WaterCloseness::Land
             };
-            if grassy_count > stony_count {
+            if snow_count > grassy_count && snow_count > stony_count {
+                Biome::Snowfield(w)
+            } else if sand_count > grassy_count && sand_count > stony_count {
+                Biome::Beach
+            } else if grassy_count > stony_count {
                 if tree_ratio > 0.1 { Biome::Forest(w) }
                 else { Biome::GrassyArea(w) }
             } else {
@@ -102,6 +123,7 @@ impl<const SIZE: usize> Level<SIZE> {
         };
         Ambient {
             biome,
+            room: self.room_at((x, y)).map(|r| r.kind),
         }
     }
 }
\ No newline at end of file
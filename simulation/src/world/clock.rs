@@ -0,0 +1,63 @@
+use std::fmt::Display;
+
+use super::SUNLIGHT;
+
+/// How many ticks make a day
+pub const TICKS_PER_DAY: u64 = 1440;
+
+/// How bright a night under the open sky is
+const MOONLIGHT: u8 = 24;
+
+/// Counts the ticks since the world was made
+#[derive(Debug, Copy, Clone)]
+pub struct Clock {
+    pub ticks: u64,
+}
+
+impl Clock {
+    /// Starts at dawn of the first day
+    pub fn new() -> Self {
+        Self { ticks: TICKS_PER_DAY / 4 }
+    }
+
+    pub fn advance(&mut self) {
+        self.ticks += 1;
+    }
+
+    pub fn day(&self) -> u64 {
+        self.ticks / TICKS_PER_DAY + 1
+    }
+
+    /// Hours and minutes, a tick is a minute
+    pub fn time_of_day(&self) -> (u64, u64) {
+        let minutes = self.ticks % TICKS_PER_DAY * 1440 / TICKS_PER_DAY;
+        (minutes / 60, minutes % 60)
+    }
+
+    /// Whenever the sun is down, so only the moon lights things up
+    pub fn is_night(&self) -> bool {
+        self.sun_height() <= 0.0
+    }
+
+    /// Light under the open sky, brightest at noon, never darker than the moonlight
+    pub fn sunlight(&self) -> u8 {
+        (MOONLIGHT as f64 + self.sun_height().max(0.0).sqrt() * (SUNLIGHT - MOONLIGHT) as f64) as u8
+    }
+
+    /// 1 at noon, -1 at midnight, 0 at dawn and dusk
+    fn sun_height(&self) -> f64 {
+        let angle = (self.ticks % TICKS_PER_DAY) as f64 / TICKS_PER_DAY as f64 * std::f64::consts::TAU;
+        -angle.cos()
+    }
+}
+
+impl Default for Clock {
+    fn default() -> Self { Self::new() }
+}
+
+impl Display for Clock {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let (hours, minutes) = self.time_of_day();
+        write!(f, "day {}, {:02}:{:02}", self.day(), hours, minutes)
+    }
+}
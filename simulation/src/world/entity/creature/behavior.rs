@@ -1,6 +1,8 @@
 use std::fmt::Display;
 
-use crate::world::SlotPosition;
+use crate::world::{SlotPosition, ItemID};
+
+use super::{SpeciesID, CreatureID};
 
 
 #[derive(Debug, Copy, Clone)]
@@ -33,22 +35,70 @@ impl Display for Interest {
     }
 }
 
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum Sense {
+    Sight,
+    Smell,
+}
+
+#[derive(Debug, Copy, Clone)]
+pub enum PerceivedKind {
+    Creature(SpeciesID, CreatureID),
+    Object(ItemID),
+    Plant,
+}
+
+/// Something noticed during the last scan
 #[derive(Debug, Copy, Clone)]
+pub struct Perceived {
+    pub position: (usize, usize),
+    pub kind: PerceivedKind,
+    pub sense: Sense,
+}
+
+#[derive(Debug, Clone)]
 pub struct Memory {
     /// Where I want to go
     pub target: Option<SlotPosition>,
+    /// Turns left before I give up on the target
+    pub target_timeout: u16,
+    /// Steps left to get where I'm going, the next one is at the end
+    pub path: Vec<(usize, usize)>,
     /// What I want now
     pub current_interest: Interest,
     /// What I wanna get back to later
     pub last_interest: Interest,
+    /// Ticks until I look around again
+    pub scan_cooldown: u8,
+    /// Where I've been lately, the latest at the end
+    pub explored: Vec<(usize, usize)>,
+    /// Where I was looking last time I moved
+    pub facing: (i8, i8),
+    /// What I noticed around me last time I looked, closest first
+    pub perceived: Vec<Perceived>,
+    /// Who attacked me, the latest at the end
+    pub grudges: Vec<CreatureID>,
+    /// Ticks until I can have offspring again
+    pub mating_cooldown: u16,
+    /// Whether I'm sleeping
+    pub asleep: bool,
 }
 
 impl Memory {
     pub fn new() -> Self {
         Self {
             target: None,
+            target_timeout: 0,
+            path: Vec::new(),
             current_interest: Interest::Idle,
             last_interest: Interest::Idle,
+            scan_cooldown: 0,
+            explored: Vec::new(),
+            facing: (0, 0),
+            perceived: Vec::new(),
+            grudges: Vec::new(),
+            mating_cooldown: 0,
+            asleep: false,
         }
     }
 }
\ No newline at end of file
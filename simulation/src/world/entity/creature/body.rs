@@ -16,4 +16,8 @@ pub struct BodyStats {
     pub smelliness: u8,
     /// how much, on average, health is removed from enemies per hit
     pub strength: u8,
+    /// how much energy is gained every tick
+    pub speed: u8,
+    /// spent to take actions, see `life::scheduler`
+    pub energy: u16,
 }
\ No newline at end of file
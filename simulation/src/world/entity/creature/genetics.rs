@@ -0,0 +1,51 @@
+use crate::util::FastRandom;
+
+use super::{Creature, CreatureID, BodyStats, BehaviorStats, Inventory, Memory};
+
+/// How far a mutation can push a stat away from the inherited value
+const MUTATION_RANGE: i16 = 8;
+
+/// One in how many stats mutate
+const MUTATION_CHANCE: u64 = 4;
+
+/// Each stat comes from either parent, with the odd mutation on top.
+/// The offspring is born healthy but only half fed.
+pub fn offspring(random: &mut FastRandom, a: &Creature, b: &Creature) -> Creature {
+    let mut inherit = |from_a: i16, from_b: i16, min: i16, max: i16| {
+        let value = if random.one_in(2) { from_a } else { from_b };
+        let value = if random.one_in(MUTATION_CHANCE) {
+            value + random.next_less_than(MUTATION_RANGE as u64 * 2 + 1) as i16 - MUTATION_RANGE
+        } else { value };
+        value.clamp(min, max)
+    };
+    let mut stat = |from_a: u8, from_b: u8| inherit(from_a as i16, from_b as i16, 1, u8::MAX as i16) as u8;
+
+    let max_health = stat(a.body.max_health, b.body.max_health);
+    let max_nutrition = stat(a.body.max_nutrition, b.body.max_nutrition);
+    let body = BodyStats {
+        max_health,
+        max_nutrition,
+        health: max_health,
+        nutrition: max_nutrition / 2,
+        karma: 0,
+        smelliness: stat(a.body.smelliness, b.body.smelliness),
+        strength: stat(a.body.strength, b.body.strength),
+        speed: stat(a.body.speed, b.body.speed),
+        energy: 0,
+    };
+    let awareness = stat(a.behavior.awareness, b.behavior.awareness);
+    let curiosity = stat(a.behavior.curiosity, b.behavior.curiosity);
+    let friendliness = inherit(a.behavior.friendliness as i16, b.behavior.friendliness as i16, i8::MIN as i16, i8::MAX as i16) as i8;
+    Creature {
+        id: CreatureID(random.next()),
+        species: a.species,
+        body,
+        behavior: BehaviorStats {
+            awareness,
+            curiosity,
+            friendliness,
+        },
+        memory: Memory::new(),
+        inventory: Inventory::default(),
+    }
+}
@@ -0,0 +1,30 @@
+use crate::world::ItemID;
+
+/// What a creature carries around
+#[derive(Debug, Clone, Default)]
+pub struct Inventory {
+    pub items: Vec<ItemID>,
+}
+
+impl Inventory {
+    pub const CAPACITY: usize = 8;
+
+    pub fn is_full(&self) -> bool {
+        self.items.len() >= Self::CAPACITY
+    }
+
+    /// Returns whether there was room for it
+    pub fn add(&mut self, item: ItemID) -> bool {
+        if self.is_full() {
+            return false;
+        }
+        self.items.push(item);
+        true
+    }
+
+    pub fn remove(&mut self, index: usize) -> Option<ItemID> {
+        if index < self.items.len() {
+            Some(self.items.remove(index))
+        } else { None }
+    }
+}
@@ -2,16 +2,23 @@ use crate::util::FastRandom;
 
 mod behavior;
 mod body;
+mod genetics;
+mod inventory;
 mod species;
 
-pub use self::{body::*, behavior::*, species::*};
+pub use self::{body::*, behavior::*, genetics::*, inventory::*, species::*};
 
-#[derive(Debug, Copy, Clone)]
+#[derive(Debug, Copy, Clone, Hash, PartialEq, Eq)]
+pub struct CreatureID(pub u64);
+
+#[derive(Debug, Clone)]
 pub struct Creature {
+    pub id: CreatureID,
     pub species: SpeciesID,
     pub body: BodyStats,
     pub behavior: BehaviorStats,
     pub memory: Memory,
+    pub inventory: Inventory,
 }
 
 impl Creature {
@@ -30,7 +37,7 @@ impl Creature {
             if matches!(self.memory.current_interest, Interest::Idle) && self.behavior.curiosity > random.next_less_than(256) as u8 {
                 self.memory.last_interest = self.memory.current_interest;
                 self.memory.current_interest = Interest::Curious;
-            } else {
+            } else if !(matches!(self.memory.current_interest, Interest::Curious) && self.memory.target.is_some()) {
                 self.memory.current_interest = self.memory.last_interest;
             }
         }
@@ -1,8 +1,8 @@
-use std::{collections::HashMap};
+use std::collections::HashMap;
 
 use crate::util::FastRandom;
 
-use super::{Creature, BehaviorStats, BodyStats, Memory};
+use super::{Creature, CreatureID, BehaviorStats, BodyStats, Inventory, Memory};
 
 pub type SpeciesMap = HashMap<SpeciesID, SpeciesTemplate>;
 
@@ -17,10 +17,17 @@ pub struct SpeciesTemplate {
     
     pub smelliness: u8,
     pub strength: u8,
+    pub speed: u8,
+    /// Whether members of this species can dig their way through rock
+    pub can_dig: bool,
     
     pub awareness: u8,
     pub curiosity: u8,
     pub friendliness: i8,
+    /// Whether members of this species sleep during the day rather than at night
+    pub nocturnal: bool,
+    /// How much members of this species like those of others, by species name
+    pub relations: HashMap<String, i8>,
 }
 
 #[derive(Debug, Copy, Clone, Hash, PartialEq, Eq)]
@@ -48,6 +55,7 @@ pub fn creature_from_species(
     let max_health = species_template.max_health;
     let max_nutrition = species_template.max_nutrition;
     Creature {
+        id: CreatureID(random.next()),
         species: species_id,
         behavior: BehaviorStats {
             awareness: species_template.awareness,
@@ -62,8 +70,11 @@ pub fn creature_from_species(
             karma: 0,
             smelliness: species_template.smelliness,
             strength: species_template.strength,
+            speed: species_template.speed,
+            energy: 0,
         },
         memory: Memory::new(),
+        inventory: Inventory::default(),
     }
 }
 
@@ -75,6 +86,7 @@ pub fn base_creature_from_species(
     let max_health = species_template.max_health;
     let max_nutrition = species_template.max_nutrition;
     Creature {
+        id: CreatureID(0),
         species: species_id,
         behavior: BehaviorStats {
             awareness: species_template.awareness,
@@ -89,7 +101,10 @@ pub fn base_creature_from_species(
             karma: 0,
             smelliness: species_template.smelliness,
             strength: species_template.strength,
+            speed: species_template.speed,
+            energy: 0,
         },
         memory: Memory::new(),
+        inventory: Inventory::default(),
     }
 }
\ No newline at end of file
@@ -0,0 +1,26 @@
+use std::collections::HashMap;
+
+pub type ItemMap = HashMap<ItemID, ItemTemplate>;
+
+#[derive(Debug, Copy, Clone, Hash, PartialEq, Eq)]
+pub struct ItemID(pub u64);
+
+#[derive(Debug)]
+pub struct ItemTemplate {
+    pub name: String,
+    pub symbol: char,
+    /// How much eating it feeds plant eaters, it can't be eaten if it's 0
+    pub nutrition: u8,
+    /// How many are scattered around each level for every 64x64 slots
+    pub abundance: u8,
+}
+
+impl ItemTemplate {
+    pub fn is_edible(&self) -> bool {
+        self.nutrition != 0
+    }
+}
+
+pub fn find_item(items: &ItemMap, name: &str) -> Option<ItemID> {
+    items.iter().find(|(_, t)| t.name == name).map(|(id, _)| *id)
+}
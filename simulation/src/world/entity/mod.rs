@@ -1,15 +1,12 @@
 use self::creature::Creature;
 
 pub mod creature;
+mod item;
 
-#[derive(Debug, Copy, Clone)]
+pub use self::item::*;
+
+#[derive(Debug, Clone)]
 pub enum Entity {
     Creature(Creature),
-    Object(ObjectType),
+    Object(ItemID),
 }
-
-#[repr(u8)]
-#[derive(Debug, Copy, Clone)]
-pub enum ObjectType {
-    Stone,
-}
\ No newline at end of file
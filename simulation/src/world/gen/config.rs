@@ -0,0 +1,76 @@
+/// The knobs of world generation. Sparsenesses are in tiles along a side, so 32 means one per 32x32 tiles.
+#[derive(Debug, Clone, PartialEq)]
+pub struct WorldGenConfig {
+    /// Overworld heights under this are deep water, between it and 0 shallow water
+    pub deep_water_level: f64,
+    pub river_sparseness: usize,
+    /// Rivers give up after running this far
+    pub river_max_length: usize,
+    /// Only ground at least this high can be where a river starts, land being between 0 and about 0.6
+    pub river_source_height: f64,
+    /// Basins bigger than this don't fill up, the river ends in the lake
+    pub max_lake_size: usize,
+    /// The lower, the fewer caves right under the surface
+    pub cave_wall_density: f64,
+    /// The lower, the fewer caves in the deep caverns
+    pub deep_cavern_wall_density: f64,
+    /// Size of the areas rooms are split in right under the surface
+    pub cave_quadrant_size: usize,
+    /// Size of the areas rooms are split in down in the dungeons
+    pub dungeon_quadrant_size: usize,
+    pub worm_cave_sparseness: usize,
+    pub portal_sparseness: usize,
+    /// Levels with little floor in common with the one above get more tries, until they have at least this many stairs
+    pub min_stairs: usize,
+    pub creature_sparseness: usize,
+    /// Items are this sparse when their abundance is 1, and as many more as their abundance otherwise
+    pub item_sparseness: usize,
+}
+
+impl WorldGenConfig {
+    /// Rooms wouldn't fit in the smallest splits of quadrants any smaller
+    pub const MIN_QUADRANT_SIZE: usize = 8;
+
+    /// What's wrong with it, if anything, like a sparseness of 0 that would have generation divide by zero
+    pub fn validate(&self) -> Result<(), String> {
+        let sparsenesses = [
+            ("river_sparseness", self.river_sparseness),
+            ("worm_cave_sparseness", self.worm_cave_sparseness),
+            ("portal_sparseness", self.portal_sparseness),
+            ("creature_sparseness", self.creature_sparseness),
+            ("item_sparseness", self.item_sparseness),
+        ];
+        if let Some((name, _)) = sparsenesses.iter().find(|(_, s)| *s == 0) {
+            return Err(format!("{name} has to be at least 1"));
+        }
+        let quadrant_sizes = [
+            ("cave_quadrant_size", self.cave_quadrant_size),
+            ("dungeon_quadrant_size", self.dungeon_quadrant_size),
+        ];
+        if let Some((name, size)) = quadrant_sizes.iter().find(|(_, s)| *s < Self::MIN_QUADRANT_SIZE) {
+            return Err(format!("{name} has to be at least {}, not {size}", Self::MIN_QUADRANT_SIZE));
+        }
+        Ok(())
+    }
+}
+
+impl Default for WorldGenConfig {
+    fn default() -> Self {
+        Self {
+            deep_water_level: -0.1,
+            river_sparseness: 32,
+            river_max_length: 512,
+            river_source_height: 0.3,
+            max_lake_size: 256,
+            cave_wall_density: -0.1,
+            deep_cavern_wall_density: 0.05,
+            cave_quadrant_size: 32,
+            dungeon_quadrant_size: 24,
+            worm_cave_sparseness: 24,
+            portal_sparseness: 32,
+            min_stairs: 16,
+            creature_sparseness: 16,
+            item_sparseness: 64,
+        }
+    }
+}
@@ -0,0 +1,84 @@
+use std::collections::VecDeque;
+
+use crate::{world::{Level, Tile}, util::FastRandom};
+
+use super::LevelGenerator;
+
+/// Tunnels every pocket of floor that can't be walked to into the largest region, the shortest way there
+pub struct ConnectRegions;
+
+impl<const SIZE: usize> LevelGenerator<SIZE> for ConnectRegions {
+    fn name(&self) -> &str { "connect regions" }
+
+    fn generate(&self, level: &mut Level<SIZE>, _random: &mut FastRandom) {
+        connect_regions(level);
+    }
+}
+
+fn connect_regions<const SIZE: usize>(level: &mut Level<SIZE>) {
+    let regions = level.regions();
+    let main = match regions.largest() {
+        Some(r) => r,
+        None => return,
+    };
+    // The first slot found of every region
+    let mut firsts = vec![None; regions.count()];
+    for i in 0..SIZE * SIZE {
+        let p = (i / SIZE, i % SIZE);
+        if let Some(r) = regions.region_at(p) {
+            firsts[r] = firsts[r].or(Some(p));
+        }
+    }
+    // What can be walked to from the main region, growing as pockets get connected
+    let mut connected = vec![false; regions.count()];
+    connected[main] = true;
+    let mut tunnels = vec![false; SIZE * SIZE];
+    for (region, from) in firsts.into_iter().enumerate() {
+        let from = match from {
+            Some(p) if !connected[region] => p,
+            _ => continue,
+        };
+        let reached = |p: (usize, usize)| tunnels[p.0 * SIZE + p.1] || regions.region_at(p).is_some_and(|r| connected[r]);
+        let tunnel = tunnel_to(level, from, reached);
+        for p in tunnel {
+            let tile = level[p].tile;
+            if !tile.is_floor() {
+                level[p].tile = tile.dug().unwrap_or(Tile::Stone);
+            }
+            tunnels[p.0 * SIZE + p.1] = true;
+        }
+        connected[region] = true;
+    }
+}
+
+/// Breadth first through anything but altars, up to the closest slot that's `reached`. The slots in between are returned.
+fn tunnel_to<const SIZE: usize>(
+    level: &Level<SIZE>,
+    from: (usize, usize),
+    reached: impl Fn((usize, usize)) -> bool,
+) -> Vec<(usize, usize)> {
+    let mut came_from = vec![usize::MAX; SIZE * SIZE];
+    let start = from.0 * SIZE + from.1;
+    came_from[start] = start;
+    let mut open = VecDeque::from([from]);
+    while let Some(pos) = open.pop_front() {
+        for n in level.neighbours(pos) {
+            let i = n.0 * SIZE + n.1;
+            if came_from[i] != usize::MAX || level[n].tile == Tile::Altar {
+                continue;
+            }
+            came_from[i] = pos.0 * SIZE + pos.1;
+            if reached(n) {
+                let mut tunnel = Vec::new();
+                let mut i = came_from[i];
+                while i != start {
+                    tunnel.push((i / SIZE, i % SIZE));
+                    i = came_from[i];
+                }
+                return tunnel;
+            }
+            open.push_back(n);
+        }
+    }
+    Vec::new()
+}
@@ -1,72 +1,265 @@
-mod open_simplex_tileable_3d;
+mod config;
+mod connectivity;
+mod noise;
+mod pipeline;
 mod underworld;
 mod overworld;
 
 use crate::util::FastRandom;
-use self::{overworld::generate_overworld, underworld::generate_underworld};
+pub use self::{config::*, connectivity::*, noise::*, pipeline::*, overworld::*, underworld::*};
 
-use super::{Tile, World, Entity, SlotPosition, Level, creature::{Creature, creature_from_species, SpeciesID, SpeciesMap, base_creature_from_species}, Ambient};
+use super::{Clock, Tile, World, Entity, ItemID, ItemMap, SlotPosition, Level, PortalMap, Regions, RoomKind, creature::{Creature, creature_from_species, SpeciesID, SpeciesMap, base_creature_from_species}, Ambient};
 
-impl<const SIZE: usize> World<SIZE, 2> {
-    pub fn generate(seed: u64) -> Self {
-        let mut overworld = generate_overworld(seed);
-        let (mut underworld, rooms) = generate_underworld(seed, 32);
-        let mut random = FastRandom::new(seed - 1);
-        place_portals(&mut random, &mut overworld, &mut underworld);
+/// What a level looks like, depending on how deep it is
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum Stratum {
+    Surface,
+    Caves,
+    Dungeons,
+    DeepCaverns,
+}
+
+impl Stratum {
+    /// The surface, then caves, then dungeons and deep caverns taking turns all the way down
+    pub fn at_depth(depth: usize) -> Self {
+        match depth {
+            0 => Stratum::Surface,
+            1 => Stratum::Caves,
+            d if d % 2 == 0 => Stratum::Dungeons,
+            _ => Stratum::DeepCaverns,
+        }
+    }
+
+    /// The passes levels of this stratum are generated with, to run as they are or tweak
+    pub fn pipeline<const SIZE: usize>(&self, config: &WorldGenConfig) -> LevelPipeline<SIZE> {
+        let worm_caves = WormCaves { sparseness: config.worm_cave_sparseness };
+        match self {
+            Stratum::Surface => LevelPipeline::new(Tile::OverworldDeepWater)
+                .under_open_sky()
+                .with(Terrain { deep_water_level: config.deep_water_level })
+                .with(Rivers {
+                    sparseness: config.river_sparseness,
+                    max_length: config.river_max_length,
+                    source_height: config.river_source_height as f32,
+                    max_lake_size: config.max_lake_size,
+                }),
+            Stratum::Caves => LevelPipeline::new(Tile::Void)
+                .with(Caves { min_wall_density: config.cave_wall_density, humidity_scale: 1.0 })
+                .with(Dungeons { quadrant_size: config.cave_quadrant_size })
+                .with(worm_caves)
+                .with(ConnectRegions),
+            Stratum::Dungeons => LevelPipeline::new(Tile::Void)
+                .with(Dungeons { quadrant_size: config.dungeon_quadrant_size })
+                .with(ConnectRegions),
+            // Wide open caves too dry for much grass
+            Stratum::DeepCaverns => LevelPipeline::new(Tile::Void)
+                .with(Caves { min_wall_density: config.deep_cavern_wall_density, humidity_scale: 0.6 })
+                .with(worm_caves)
+                .with(ConnectRegions),
+        }
+    }
+}
+
+impl<const SIZE: usize, const H: usize> World<SIZE, H> {
+    pub fn generate(seed: u64, config: &WorldGenConfig) -> Self {
+        Self::generate_with(seed, config, |depth| Stratum::at_depth(depth).pipeline(config))
+    }
+
+    /// Every level is generated from the seed with the pipeline for its depth, then adjacent ones are linked by stairs
+    pub fn generate_with(seed: u64, config: &WorldGenConfig, pipeline: impl Fn(usize) -> LevelPipeline<SIZE>) -> Self {
+        if let Err(e) = config.validate() {
+            panic!("Invalid world generation config: {e}");
+        }
+        let mut levels: [Level<SIZE>; H] = std::array::from_fn(|depth| pipeline(depth).run(seed, depth));
+        let mut random = FastRandom::new(seed.wrapping_sub(1));
+        let mut portals = PortalMap::new();
+        for l in 1..H {
+            let (upper, lower) = levels.split_at_mut(l);
+            for (d, u) in place_portals(&mut random, config, &mut upper[l - 1], &mut lower[0]) {
+                let down = SlotPosition { x: d.0, y: d.1, level: l - 1 };
+                let up = SlotPosition { x: u.0, y: u.1, level: l };
+                portals.insert(down, up);
+                portals.insert(up, down);
+            }
+        }
+        for level in levels.iter_mut() {
+            mark_entrances(level);
+        }
+        let clock = Clock::new();
+        for level in levels.iter_mut() {
+            if level.open_sky {
+                level.sky_light = clock.sunlight();
+            }
+            level.update_light();
+            level.fill_water();
+        }
         Self {
-            levels: [overworld, underworld],
+            levels,
             player_position: SlotPosition { x: 0, y: 0, level: 0 },
             species: SpeciesMap::new(),
+            items: ItemMap::new(),
+            portals,
+            clock,
         }
     }
 
-    pub fn create_life(&mut self, seed: u64, species: SpeciesMap, player_species: SpeciesID) {
-        let mut random = FastRandom::new(seed - 2);
-        let sparseness = 16;
+    pub fn create_life(&mut self, seed: u64, config: &WorldGenConfig, species: SpeciesMap, player_species: SpeciesID) {
+        let mut random = FastRandom::new(seed.wrapping_sub(2));
+        let sparseness = config.creature_sparseness;
         for level in &mut self.levels {
+            let regions = level.regions();
             for _ in 0..(SIZE / sparseness * SIZE / sparseness) {
-                let f = level.find_floor(&mut random);
+                let f = find_main_floor(level, &regions, &mut random);
                 let ambient = level.get_ambient_at(f.0, f.1, 12);
                 let mut s = &mut level[f];
                 s.entity = Some(Entity::Creature(generate_creature(&species, &mut random, &ambient)));
             }
+            // Lairs get a few more on top
+            for room in level.rooms.clone() {
+                if room.kind != RoomKind::Lair {
+                    continue;
+                }
+                let count = (room.inside().count() / 24).clamp(1, 4);
+                for _ in 0..count {
+                    if let Some(f) = find_floor_in(level, &room.inside().collect::<Vec<_>>(), &mut random) {
+                        let ambient = level.get_ambient_at(f.0, f.1, 12);
+                        level[f].entity = Some(Entity::Creature(generate_creature(&species, &mut random, &ambient)));
+                    }
+                }
+            }
         }
-        let player_xy = self.levels[1].find_floor(&mut random);
-        let player_position = SlotPosition { x: player_xy.0, y: player_xy.1, level: 1 };
+        // Right under the surface, if there's anything under it
+        let player_level = 1.min(H - 1);
+        let level = &self.levels[player_level];
+        let player_xy = find_main_floor(level, &level.regions(), &mut random);
+        let player_position = SlotPosition { x: player_xy.0, y: player_xy.1, level: player_level };
         self[&player_position].entity = Some(Entity::Creature(
             base_creature_from_species(&species, player_species)
         ));
         self.player_position = player_position;
         self.species = species;
     }
+
+    pub fn scatter_items(&mut self, seed: u64, config: &WorldGenConfig, items: ItemMap) {
+        let mut random = FastRandom::new(seed.wrapping_sub(3));
+        // Same seed, same items in the same places, whatever order the map is in and whichever IDs they got
+        let mut ids: Vec<ItemID> = items.keys().copied().collect();
+        ids.sort_by(|a, b| items[a].name.cmp(&items[b].name));
+        for level in &mut self.levels {
+            for id in &ids {
+                for _ in 0..(SIZE / config.item_sparseness * SIZE / config.item_sparseness * items[id].abundance as usize) {
+                    let f = level.find_floor(&mut random);
+                    level[f].entity = Some(Entity::Object(*id));
+                }
+            }
+            if ids.is_empty() {
+                continue;
+            }
+            for room in level.rooms.clone() {
+                if room.kind != RoomKind::Storage {
+                    continue;
+                }
+                let inside: Vec<_> = room.inside().collect();
+                for _ in 0..(inside.len() / 12).max(1) {
+                    if let Some(f) = find_floor_in(level, &inside, &mut random) {
+                        let id = ids[random.next_less_than(ids.len() as u64) as usize];
+                        level[f].entity = Some(Entity::Object(id));
+                    }
+                }
+            }
+        }
+        self.items = items;
+    }
+}
+
+/// Links a level with the one under it, with stairs down in the largest region of the upper one, away from water,
+/// each leading to stairs up somewhere in the largest region of the lower one.
+/// Keeps trying a while longer until there are at least a few of them.
+/// Returns where the stairs down and the stairs up they lead to were placed.
+fn place_portals<const SIZE: usize>(
+    random: &mut FastRandom,
+    config: &WorldGenConfig,
+    upper: &mut Level<SIZE>,
+    lower: &mut Level<SIZE>,
+) -> Vec<((usize, usize), (usize, usize))> {
+    const MAX_EXTRA_TRIES: usize = 4096;
+    let (upper_regions, lower_regions) = (upper.regions(), lower.regions());
+    let tries = SIZE / config.portal_sparseness * SIZE / config.portal_sparseness;
+    let mut placed = Vec::new();
+    for i in 0..(tries + MAX_EXTRA_TRIES) {
+        if i >= tries && placed.len() >= config.min_stairs {
+            break;
+        }
+        let (down, up) = match (main_spot(random, &upper_regions, upper), main_spot(random, &lower_regions, lower)) {
+            (Some(down), Some(up)) => (down, up),
+            _ => continue,
+        };
+        // Water would pour down them forever
+        if upper.neighbours(down).iter().any(|n| upper[*n].tile.is_swimmable()) {
+            continue;
+        }
+        upper[down].tile = Tile::StairsDown;
+        lower[up].tile = Tile::StairsUp;
+        placed.push((down, up));
+    }
+    placed
+}
+
+/// Somewhere in the largest region without stairs yet, if it turns up after a few tries
+fn main_spot<const SIZE: usize>(random: &mut FastRandom, regions: &Regions<SIZE>, level: &Level<SIZE>) -> Option<(usize, usize)> {
+    for _ in 0..16 {
+        let p = (random.next_less_than(SIZE as u64) as usize, random.next_less_than(SIZE as u64) as usize);
+        if regions.region_at(p) == regions.largest() && !level[p].tile.is_stairs() {
+            return Some(p);
+        }
+    }
+    None
+}
+
+/// Rooms with stairs in them are where one comes in from other levels
+fn mark_entrances<const SIZE: usize>(level: &mut Level<SIZE>) {
+    for i in 0..level.rooms.len() {
+        let room = level.rooms[i];
+        if room.kind == RoomKind::Plain && room.inside().any(|p| level[p].tile.is_stairs()) {
+            level.rooms[i].kind = RoomKind::Entrance;
+        }
+    }
+}
+
+/// Like `Level::find_floor`, but only in the largest region, so there's a way from there to everywhere that matters
+fn find_main_floor<const SIZE: usize>(level: &Level<SIZE>, regions: &Regions<SIZE>, random: &mut FastRandom) -> (usize, usize) {
+    let main = regions.largest();
+    loop {
+        let f = level.find_floor(random);
+        if regions.region_at(f) == main {
+            return f;
+        }
+    }
 }
 
-fn place_portals<const SIZE: usize>(random: &mut FastRandom, overworld: &mut Level<SIZE>, dungeon: &mut Level<SIZE>) {
-    for _ in 0..(SIZE / 32 * SIZE / 32) {
-        let (x, y) = (random.next_less_than(SIZE as u64), random.next_less_than(SIZE as u64));
-        let mut overworld_slot = &mut overworld[(x as usize, y as usize)];
-        let mut dungeon_slot = &mut dungeon[(x as usize, y as usize)];
-        if overworld_slot.tile.is_floor() && dungeon_slot.tile.is_floor() {
-            overworld_slot.tile = Tile::GotoUnderworld;
-            dungeon_slot.tile = Tile::GotoOverworld;
+/// A free floor slot among `positions`, if one turns up after a few tries
+fn find_floor_in<const SIZE: usize>(level: &Level<SIZE>, positions: &[(usize, usize)], random: &mut FastRandom) -> Option<(usize, usize)> {
+    for _ in 0..8 {
+        let p = positions[random.next_less_than(positions.len() as u64) as usize];
+        if level[p].tile.is_floor() && level[p].entity.is_none() {
+            return Some(p);
         }
     }
+    None
 }
 
 fn generate_creature(species: &SpeciesMap, random: &mut FastRandom, ambient: &Ambient) -> Creature {
     creature_from_species(random, species, SpeciesID(0))
 }
 
+/// A winding tunnel of `tile` between the two points, through the void only
 fn carve_from<const SIZE: usize>(
     level: &mut Level<SIZE>,
     random: &mut FastRandom,
     max_distance: usize,
-    mut from_x: usize,
-    mut from_y: usize,
-    to_x: usize,
-    to_y: usize,
+    (mut from_x, mut from_y): (usize, usize),
+    (to_x, to_y): (usize, usize),
     tile: Tile,
-    only_on_void: bool,
 ) {
     let length = ((from_x.abs_diff(to_x).pow(2) + from_y.abs_diff(to_y).pow(2)) as f64).sqrt() as usize;
     if length >= max_distance {
@@ -86,7 +279,7 @@ fn carve_from<const SIZE: usize>(
                 let x = (bx + SIZE as i64) as usize % SIZE;
                 let y = (by + SIZE as i64) as usize % SIZE;
                 let c = (x, y);
-                if !only_on_void || matches!(level[c].tile, Tile::Void) {
+                if matches!(level[c].tile, Tile::Void) {
                     level[c].tile = tile;
                 }
             }
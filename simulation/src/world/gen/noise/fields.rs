@@ -0,0 +1,36 @@
+use super::{super::fnv1a, DomainWarp, Fbm, NoiseLayer};
+
+/// Every noise for a level from one seed, each told apart by its name instead of by where it's sampled
+pub struct NoiseFields {
+    seed: u64,
+    size: usize,
+}
+
+impl NoiseFields {
+    pub fn new(seed: u64, size: usize) -> Self {
+        Self { seed, size }
+    }
+
+    /// The seed of the field called `name`, the same across builds and platforms
+    pub fn seed(&self, name: &str) -> u64 {
+        fnv1a(self.seed.to_le_bytes().into_iter().chain(name.bytes()))
+    }
+
+    pub fn layer(&self, name: &str, feature_size: f64) -> NoiseLayer {
+        NoiseLayer::new(self.seed(name), self.size, feature_size)
+    }
+
+    pub fn fbm(&self, name: &str, feature_size: f64, octaves: usize, gain: f64) -> Fbm {
+        Fbm::new(self.seed(name), self.size, feature_size, octaves, gain)
+    }
+
+    /// A field pushed around by two layers about as big as its own features
+    pub fn warped_fbm(&self, name: &str, feature_size: f64, octaves: usize, gain: f64, strength: f64) -> DomainWarp<Fbm, NoiseLayer> {
+        DomainWarp {
+            noise: self.fbm(name, feature_size, octaves, gain),
+            warp_x: self.layer(&format!("{name} warp x"), feature_size),
+            warp_y: self.layer(&format!("{name} warp y"), feature_size),
+            strength,
+        }
+    }
+}
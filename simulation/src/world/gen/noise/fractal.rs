@@ -0,0 +1,90 @@
+use crate::util::FastRandom;
+
+use super::SimplexTileable2D;
+
+/// Noise over a level, around 0 on average
+pub trait Noise2D {
+    fn sample(&self, x: f64, y: f64) -> f64;
+}
+
+/// Simplex noise with features about `feature_size` slots across, repeating every `size` slots like the level does
+pub struct NoiseLayer {
+    noise: SimplexTileable2D,
+    frequency: f64,
+}
+
+impl NoiseLayer {
+    /// The feature size gets rounded a bit, so a whole number of periods fits in the level
+    pub fn new(seed: u64, size: usize, feature_size: f64) -> Self {
+        let cells = ((size as f64 / feature_size / 3.0).round() as u32).max(1) * 3;
+        Self {
+            noise: SimplexTileable2D::new(seed, cells, cells),
+            frequency: cells as f64 / size as f64,
+        }
+    }
+}
+
+impl Noise2D for NoiseLayer {
+    fn sample(&self, x: f64, y: f64) -> f64 {
+        self.noise.eval(x * self.frequency, y * self.frequency)
+    }
+}
+
+/// Fractal brownian motion: octaves of noise, each half the size and `gain` times as strong as the one before
+pub struct Fbm {
+    octaves: Vec<NoiseLayer>,
+    gain: f64,
+}
+
+impl Fbm {
+    pub fn new(seed: u64, size: usize, feature_size: f64, octaves: usize, gain: f64) -> Self {
+        let mut random = FastRandom::new(seed);
+        Self {
+            octaves: (0..octaves)
+                .map(|o| NoiseLayer::new(random.next(), size, feature_size / (1 << o) as f64))
+                .collect(),
+            gain,
+        }
+    }
+}
+
+impl Noise2D for Fbm {
+    /// Scaled back down by how strong all the octaves are together
+    fn sample(&self, x: f64, y: f64) -> f64 {
+        let mut value = 0.0;
+        let mut amplitude = 1.0;
+        let mut total = 0.0;
+        for octave in &self.octaves {
+            value += octave.sample(x, y) * amplitude;
+            total += amplitude;
+            amplitude *= self.gain;
+        }
+        value / total
+    }
+}
+
+/// Sharp crests where the noise crosses zero and wide troughs in between, like mountain ridges
+pub struct Ridged<N: Noise2D>(pub N);
+
+impl<N: Noise2D> Noise2D for Ridged<N> {
+    fn sample(&self, x: f64, y: f64) -> f64 {
+        1.0 - 2.0 * self.0.sample(x, y).abs()
+    }
+}
+
+/// Samples the noise somewhere else, pushed around by up to `strength` slots by two other noises, to twist it out of shape
+pub struct DomainWarp<N: Noise2D, W: Noise2D> {
+    pub noise: N,
+    pub warp_x: W,
+    pub warp_y: W,
+    pub strength: f64,
+}
+
+impl<N: Noise2D, W: Noise2D> Noise2D for DomainWarp<N, W> {
+    fn sample(&self, x: f64, y: f64) -> f64 {
+        self.noise.sample(
+            x + self.warp_x.sample(x, y) * self.strength,
+            y + self.warp_y.sample(x, y) * self.strength,
+        )
+    }
+}
@@ -0,0 +1,5 @@
+mod fields;
+mod fractal;
+mod simplex_tileable;
+
+pub use self::{fields::*, fractal::*, simplex_tileable::*};
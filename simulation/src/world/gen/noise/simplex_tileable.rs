@@ -0,0 +1,136 @@
+use crate::util::FastRandom;
+
+/// Simplex noise that repeats. Regular 2D and 4D simplex lattices never line back up with the axes,
+/// so this one's squashed a little, enough for its corners to line up again every 3 cells.
+/// Each period is in cells and should be a multiple of 3, or 0 for an axis that doesn't repeat.
+struct TileableSimplex<const N: usize> {
+    perm: [u8; 256],
+    periods: [i64; N],
+}
+
+impl<const N: usize> TileableSimplex<N> {
+    /// Corners are skewed by a third of their sum, and a corner's real position is its skewed one minus this much of that sum
+    const UNSKEW: f64 = 1.0 / (N as f64 + 3.0);
+
+    fn new(seed: u64, periods: [u32; N]) -> Self {
+        assert!(periods.iter().all(|p| p % 3 == 0), "periods must be multiples of 3");
+        let mut random = FastRandom::new(seed);
+        let mut perm = [0u8; 256];
+        for (i, p) in perm.iter_mut().enumerate() {
+            *p = i as u8;
+        }
+        for i in (1..256).rev() {
+            perm.swap(i, random.next_less_than(i as u64 + 1) as usize);
+        }
+        Self { perm, periods: periods.map(|p| p as i64) }
+    }
+
+    /// Sum of what the simplex' corners contribute, fading out `radius_sq` away from each of them
+    fn eval(&self, point: [f64; N], gradients: &[[f64; N]], radius_sq: f64) -> f64 {
+        let skew = point.iter().sum::<f64>() / 3.0;
+        let mut corner = [0i64; N];
+        let mut inside = [0.0; N];
+        for i in 0..N {
+            let s = point[i] + skew;
+            corner[i] = s.floor() as i64;
+            inside[i] = s - corner[i] as f64;
+        }
+        // Stepping along the axes it's furthest in along goes through every corner of the simplex it's in
+        let mut order = [0; N];
+        for i in 0..N {
+            let mut j = i;
+            while j > 0 && inside[order[j - 1]] < inside[i] {
+                order[j] = order[j - 1];
+                j -= 1;
+            }
+            order[j] = i;
+        }
+        let mut sum: i64 = corner.iter().sum();
+        let mut value = 0.0;
+        for step in 0..=N {
+            if step > 0 {
+                corner[order[step - 1]] += 1;
+                sum += 1;
+            }
+            let unskew = sum as f64 * Self::UNSKEW;
+            let mut offset = [0.0; N];
+            let mut attenuation = radius_sq;
+            for i in 0..N {
+                offset[i] = point[i] - (corner[i] as f64 - unskew);
+                attenuation -= offset[i] * offset[i];
+            }
+            if attenuation > 0.0 {
+                let gradient = &gradients[self.hash(&corner, sum) as usize % gradients.len()];
+                let dot: f64 = gradient.iter().zip(&offset).map(|(g, o)| g * o).sum();
+                value += attenuation.powi(4) * dot;
+            }
+        }
+        value
+    }
+
+    /// Hashes where the corner really is, wrapped to the periods, so corners a period apart get the same gradient
+    fn hash(&self, corner: &[i64; N], sum: i64) -> u8 {
+        // Its real position times N + 3, to keep it whole
+        let scale = N as i64 + 3;
+        let mut hash = 0u8;
+        for (&corner, &period) in corner.iter().zip(&self.periods) {
+            let mut c = scale * corner - sum;
+            if period > 0 {
+                c = c.rem_euclid(scale * period);
+            }
+            hash = self.perm[(hash as usize + (c & 0xFF) as usize) & 0xFF];
+        }
+        hash
+    }
+}
+
+/// 2D simplex noise that repeats every `width` cells along x and `height` along y.
+/// It stays within about ±0.6.
+pub struct SimplexTileable2D(TileableSimplex<2>);
+
+impl SimplexTileable2D {
+    const RADIUS_SQ: f64 = 0.5;
+    const NORM: f64 = 57.0;
+
+    /// Every 30 degrees
+    const GRADIENTS: [[f64; 2]; 12] = [
+        [1.0, 0.0], [0.866, 0.5], [0.5, 0.866], [0.0, 1.0], [-0.5, 0.866], [-0.866, 0.5],
+        [-1.0, 0.0], [-0.866, -0.5], [-0.5, -0.866], [0.0, -1.0], [0.5, -0.866], [0.866, -0.5],
+    ];
+
+    pub fn new(seed: u64, width: u32, height: u32) -> Self {
+        Self(TileableSimplex::new(seed, [width, height]))
+    }
+
+    pub fn eval(&self, x: f64, y: f64) -> f64 {
+        self.0.eval([x, y], &Self::GRADIENTS, Self::RADIUS_SQ) * Self::NORM
+    }
+}
+
+/// 4D simplex noise between -1 and 1, repeating along each axis with a non zero period
+pub struct SimplexTileable4D(TileableSimplex<4>);
+
+impl SimplexTileable4D {
+    const RADIUS_SQ: f64 = 0.47;
+    const NORM: f64 = 80.0;
+
+    /// Towards the middles of the edges of a tesseract
+    const GRADIENTS: [[f64; 4]; 32] = [
+        [0.0, 1.0, 1.0, 1.0], [0.0, 1.0, 1.0, -1.0], [0.0, 1.0, -1.0, 1.0], [0.0, 1.0, -1.0, -1.0],
+        [0.0, -1.0, 1.0, 1.0], [0.0, -1.0, 1.0, -1.0], [0.0, -1.0, -1.0, 1.0], [0.0, -1.0, -1.0, -1.0],
+        [1.0, 0.0, 1.0, 1.0], [1.0, 0.0, 1.0, -1.0], [1.0, 0.0, -1.0, 1.0], [1.0, 0.0, -1.0, -1.0],
+        [-1.0, 0.0, 1.0, 1.0], [-1.0, 0.0, 1.0, -1.0], [-1.0, 0.0, -1.0, 1.0], [-1.0, 0.0, -1.0, -1.0],
+        [1.0, 1.0, 0.0, 1.0], [1.0, 1.0, 0.0, -1.0], [1.0, -1.0, 0.0, 1.0], [1.0, -1.0, 0.0, -1.0],
+        [-1.0, 1.0, 0.0, 1.0], [-1.0, 1.0, 0.0, -1.0], [-1.0, -1.0, 0.0, 1.0], [-1.0, -1.0, 0.0, -1.0],
+        [1.0, 1.0, 1.0, 0.0], [1.0, 1.0, -1.0, 0.0], [1.0, -1.0, 1.0, 0.0], [1.0, -1.0, -1.0, 0.0],
+        [-1.0, 1.0, 1.0, 0.0], [-1.0, 1.0, -1.0, 0.0], [-1.0, -1.0, 1.0, 0.0], [-1.0, -1.0, -1.0, 0.0],
+    ];
+
+    pub fn new(seed: u64, periods: [u32; 4]) -> Self {
+        Self(TileableSimplex::new(seed, periods))
+    }
+
+    pub fn eval(&self, x: f64, y: f64, z: f64, w: f64) -> f64 {
+        self.0.eval([x, y, z, w], &Self::GRADIENTS, Self::RADIUS_SQ) * Self::NORM
+    }
+}
@@ -1,114 +1,324 @@
-use crate::{world::{Level, Slot, Tile}, util::FastRandom};
+use std::{cmp::Reverse, collections::{BinaryHeap, HashSet}};
 
-use super::{open_simplex_tileable_3d::OpenSimplexTileable3D, carve_from};
+use crate::{world::{Level, Tile}, util::FastRandom};
 
-pub(super) fn generate_overworld<const SIZE: usize>(seed: u64) -> Level<SIZE> {
-    let mut level = Level {
-        slots: vec![Slot {
-            tile: Tile::OverworldDeepWater,
-            entity: None,
-        }; SIZE * SIZE].into_boxed_slice()
-    };
-    let mut random = FastRandom::new(seed);
-    place_terrain(&mut level, &mut random);
-    carve_rivers(&mut level, &mut random);
-    level
+use super::{LevelGenerator, Noise2D, NoiseFields, Ridged};
+
+/// Seas, land and what grows on it, from height, temperature and humidity
+pub struct Terrain {
+    pub deep_water_level: f64,
+}
+
+/// Rivers running downhill from high ground, merging and widening on their way to the sea, with lakes where they get stuck
+pub struct Rivers {
+    pub sparseness: usize,
+    /// Rivers give up after this many steps
+    pub max_length: usize,
+    /// Only ground at least this high can be where a river starts
+    pub source_height: f32,
+    /// Basins bigger than this don't fill up, the river ends in the lake
+    pub max_lake_size: usize,
+}
+
+impl<const SIZE: usize> LevelGenerator<SIZE> for Terrain {
+    fn name(&self) -> &str { "terrain" }
+
+    fn generate(&self, level: &mut Level<SIZE>, random: &mut FastRandom) {
+        place_terrain(level, random, self.deep_water_level);
+    }
+}
+
+impl<const SIZE: usize> LevelGenerator<SIZE> for Rivers {
+    fn name(&self) -> &str { "rivers" }
+
+    fn generate(&self, level: &mut Level<SIZE>, random: &mut FastRandom) {
+        carve_rivers(level, random, self);
+    }
 }
 
 fn place_terrain<const SIZE: usize>(
     level: &mut Level<SIZE>,
     random: &mut FastRandom,
+    deep_water_level: f64,
 ) {
-    let s = SIZE as i32 / 6;
-    let os64 = OpenSimplexTileable3D::new_with_seed_square(random.next() as i64, s / 64);
-    let os16 = OpenSimplexTileable3D::new_with_seed_square(random.next() as i64, s / 16);
-    let os8 = OpenSimplexTileable3D::new_with_seed_square(random.next() as i64, s / 8);
+    let fields = NoiseFields::new(random.next(), SIZE);
+    let continents = fields.warped_fbm("height", 64.0, 2, 0.25, 16.0);
+    let roughness = fields.layer("roughness", 64.0);
+    let hills = fields.fbm("hills", 16.0, 2, 0.67);
+    let ridges = Ridged(fields.fbm("ridges", 32.0, 2, 0.5));
+    let temperature = fields.fbm("temperature", 64.0, 2, 0.25);
+    let humidity = fields.fbm("humidity", 64.0, 2, 0.43);
     for xi in 0..SIZE {
         for yi in 0..SIZE {
-            const DEEP_WATER_LEVEL: f64 = -0.1;
-            const TEMPERATURE_BEACH_WEIGHT: f64 = 0.4;
             let (x, y) = (xi as f64, yi as f64);
-            let height_frequency = (os64.eval(x / 64.0, y / 64.0, 192.0) * 0.5 + 0.5).powi(2);
-            let inv_height_frequency = 1.0 - height_frequency;
-            let weights = [
-                1.0 * inv_height_frequency + 0.0 * height_frequency,
-                0.0 * inv_height_frequency + 0.6 * height_frequency,
-                0.0 * inv_height_frequency + 0.4 * height_frequency,
-            ];
-            let height = 0.1 + os64.eval(x / 64.0, y / 64.0, 0.0) * weights[0];
-            let height = if
-                height + weights[1] + weights[2] < DEEP_WATER_LEVEL ||
-                height - weights[1] - weights[2] > TEMPERATURE_BEACH_WEIGHT {
-                    height
-                } else { height + os16.eval(x / 16.0, y / 16.0, 0.0) * weights[1] };
-            let height = if
-                height + weights[2] < DEEP_WATER_LEVEL ||
-                height - weights[2] > TEMPERATURE_BEACH_WEIGHT {
-                    height
-                } else { height + os8.eval(x / 8.0, y / 8.0, 0.0) * weights[2] };
-
-            level[(xi, yi)].tile = if height < DEEP_WATER_LEVEL {
+            let height = continents.sample(x, y);
+            // Smooth lowlands, getting rougher the higher they go, and more so in some places than others
+            let rough = ((height + roughness.sample(x, y) * 0.5) * 0.5 + 0.5).clamp(0.0, 1.0).powi(2);
+            let detail = hills.sample(x, y) * 0.6 + ridges.sample(x, y) * 0.4;
+            let height = height * (1.0 - rough) + detail * rough;
+            let temperature = temperature.sample(x, y);
+            let humidity = humidity.sample(x, y);
+            level[(xi, yi)].humidity = ((humidity * 0.5 + 0.5).clamp(0.0, 1.0) * 255.0) as u8;
+            level[(xi, yi)].height = height as f32;
+
+            level[(xi, yi)].tile = if height < deep_water_level {
                 Tile::OverworldDeepWater
             } else if height <= -0.0 {
                 Tile::OverworldWater
-            } else if random.one_in(12) {
-                Tile::OverworldScraps
             } else {
-                Tile::OverworldGravel
+                land_tile(random, height, temperature, humidity)
             };
         }
     }
 }
 
+/// Picks the ground and what grows on it from how high, warm and humid it is
+fn land_tile(random: &mut FastRandom, height: f64, temperature: f64, humidity: f64) -> Tile {
+    const BEACH_HEIGHT: f64 = 0.06;
+    const COLD: f64 = -0.25;
+    const HOT: f64 = 0.25;
+    const DRY: f64 = -0.2;
+    const HUMID: f64 = 0.2;
+    if temperature < COLD {
+        // Snowy spruce forests, thicker where it's humid
+        if humidity > DRY && random.next_less_than(256) < ((humidity - DRY) * 160.0) as u64 {
+            Tile::SpruceTree
+        } else { Tile::Snow }
+    } else if height < BEACH_HEIGHT {
+        if temperature > HOT && random.one_in(10) { Tile::PalmTree } else { Tile::Sand }
+    } else if temperature > HOT && humidity < HUMID {
+        // Savannas
+        if random.one_in(24) { Tile::AcaciaTree }
+        else if humidity < DRY && random.one_in(3) { Tile::Sand }
+        else { Tile::ThinGrass }
+    } else if humidity > HUMID {
+        // Forests
+        match random.next_less_than(16) {
+            0..=3 => Tile::OakTree,
+            4..=5 => Tile::BirchTree,
+            6 => if random.one_in(16) { Tile::GoldenTree } else { Tile::OakTree },
+            7..=9 => Tile::ThinGrass,
+            _ => Tile::Grass,
+        }
+    } else if humidity > DRY {
+        // Meadows
+        match random.next_less_than(64) {
+            0 => Tile::PeachTree,
+            1 => Tile::OakTree,
+            2..=20 => Tile::ThinGrass,
+            _ => Tile::Grass,
+        }
+    } else if random.one_in(12) {
+        Tile::OverworldScraps
+    } else {
+        Tile::OverworldGravel
+    }
+}
+
+/// Slots no river runs on from
+const NO_DOWNSTREAM: u32 = u32::MAX;
+/// Slots no river or lake is on
+const NO_RIVER: u32 = u32::MAX;
+
+/// Where the rivers run, worked out before any of them is carved
+struct Drainage {
+    /// How many rivers run through each slot
+    flow: Vec<u16>,
+    /// How far each slot is from the source of the longest river running through it, as far as a u16 goes
+    length: Vec<u16>,
+    /// Where the water goes from each slot
+    downstream: Vec<u32>,
+    lake: Vec<bool>,
+    /// Which river got to each slot first, so one doesn't take its own upstream for somewhere to flow on to
+    river: Vec<u32>,
+}
+
 fn carve_rivers<const SIZE: usize>(
     level: &mut Level<SIZE>,
     random: &mut FastRandom,
+    rivers: &Rivers,
 ) {
-    let river_sparceness = 32;
-    for _ in 0..(SIZE / river_sparceness * SIZE / river_sparceness) {
-        let (from_x, from_y) = level.find_floor(random);
-        let max_distance = 192;
-        if let Some((to_x, to_y)) = closest_water_tile(random, level, from_x, from_y, max_distance) {
-            carve_from(level, random, max_distance, from_x, from_y, to_x, to_y, Tile::OverworldWater, false);
+    let drainage = trace_drainage(level, random, rivers);
+    // The more water, and the further from the source, the wider
+    for i in 0..SIZE * SIZE {
+        if drainage.flow[i] == 0 || drainage.lake[i] {
+            continue;
+        }
+        let radius = (0.4 + (drainage.flow[i] as f64).sqrt() * 0.35 + drainage.length[i] as f64 / 128.0).min(3.0);
+        let (x, y) = (i / SIZE, i % SIZE);
+        let r = radius as i64;
+        for dx in -r..=r {
+            for dy in -r..=r {
+                if (dx * dx + dy * dy) as f64 > radius * radius {
+                    continue;
+                }
+                let p = ((x as i64 + dx + SIZE as i64) as usize % SIZE, (y as i64 + dy + SIZE as i64) as usize % SIZE);
+                if !level[p].tile.is_swimmable() {
+                    level[p].tile = Tile::OverworldWater;
+                }
+            }
         }
     }
 }
 
-fn closest_water_tile<const SIZE: usize>(
+fn trace_drainage<const SIZE: usize>(
+    level: &mut Level<SIZE>,
     random: &mut FastRandom,
+    rivers: &Rivers,
+) -> Drainage {
+    let mut drainage = Drainage {
+        flow: vec![0; SIZE * SIZE],
+        length: vec![0; SIZE * SIZE],
+        downstream: vec![NO_DOWNSTREAM; SIZE * SIZE],
+        lake: vec![false; SIZE * SIZE],
+        river: vec![NO_RIVER; SIZE * SIZE],
+    };
+    for river in 0..(SIZE / rivers.sparseness * SIZE / rivers.sparseness) {
+        if let Some(source) = find_river_source(level, random, rivers.source_height) {
+            trace_river(level, &mut drainage, rivers, river as u32, source);
+        }
+    }
+    drainage
+}
+
+fn find_river_source<const SIZE: usize>(
     level: &Level<SIZE>,
-    x: usize,
-    y: usize,
-    max_axis_distance: usize,
+    random: &mut FastRandom,
+    min_height: f32,
 ) -> Option<(usize, usize)> {
-    let mut dist = 1;
-    'l: loop {
-        if random.one_in(2) {
-            continue;
+    for _ in 0..16 {
+        let p = (random.next_less_than(SIZE as u64) as usize, random.next_less_than(SIZE as u64) as usize);
+        if level[p].height >= min_height && !level[p].tile.is_swimmable() {
+            return Some(p);
         }
-        for x in (x as i64 - dist)..=(x as i64 + dist) {
-            let c = ((x + SIZE as i64) as usize % SIZE, (y as i64 - dist + SIZE as i64) as usize % SIZE);
-            if matches!(level[c].tile, Tile::OverworldWater | Tile::OverworldDeepWater) {
-                break 'l Some(c);
-            }
-            let c = ((x + SIZE as i64) as usize % SIZE, (y as i64 + dist) as usize % SIZE);
-            if matches!(level[c].tile, Tile::OverworldWater | Tile::OverworldDeepWater) {
-                break 'l Some(c);
+    }
+    None
+}
+
+/// Follows the steepest way down until the sea, a lake or another river, filling the basins it gets stuck in
+fn trace_river<const SIZE: usize>(
+    level: &mut Level<SIZE>,
+    drainage: &mut Drainage,
+    rivers: &Rivers,
+    river: u32,
+    source: (usize, usize),
+) {
+    let mut pos = source;
+    for steps in 0..rivers.max_length {
+        let i = pos.0 * SIZE + pos.1;
+        if drainage.flow[i] > 0 || drainage.lake[i] {
+            // Joined another river, which carries more water from here on
+            let mut j = i as u32;
+            for _ in 0..rivers.max_length {
+                if j == NO_DOWNSTREAM {
+                    break;
+                }
+                drainage.flow[j as usize] = drainage.flow[j as usize].saturating_add(1);
+                j = drainage.downstream[j as usize];
             }
+            return;
         }
-        for y in (y as i64 - dist + 1)..(y as i64 + dist) {
-            let c = ((x as i64 - dist + SIZE as i64) as usize % SIZE, (y + SIZE as i64) as usize % SIZE);
-            if matches!(level[c].tile, Tile::OverworldWater | Tile::OverworldDeepWater) {
-                break 'l Some(c);
-            }
-            let c = ((x as i64 + dist) as usize % SIZE, (y + SIZE as i64) as usize % SIZE);
-            if matches!(level[c].tile, Tile::OverworldWater | Tile::OverworldDeepWater) {
-                break 'l Some(c);
+        if level[pos].tile.is_swimmable() {
+            return;
+        }
+        drainage.flow[i] = 1;
+        drainage.river[i] = river;
+        drainage.length[i] = steps.min(u16::MAX as usize) as u16;
+        let lowest = level.neighbours(pos).into_iter()
+            .min_by(|a, b| level[*a].height.total_cmp(&level[*b].height))
+            .unwrap();
+        let next = if level[lowest].height < level[pos].height {
+            Some(lowest)
+        } else {
+            fill_basin(level, drainage, rivers.max_lake_size, river, pos)
+        };
+        match next {
+            Some(next) => {
+                drainage.downstream[i] = (next.0 * SIZE + next.1) as u32;
+                pos = next;
+            },
+            None => return,
+        }
+    }
+}
+
+/// Floods the basin around `start` up to the lowest point of its rim, which is where the water flows on from.
+/// Basins too big to fill up end up as lakes with no way out.
+/// Wherever `river` has been already is part of the basin, as flowing on there would go round in circles.
+fn fill_basin<const SIZE: usize>(
+    level: &mut Level<SIZE>,
+    drainage: &mut Drainage,
+    max_size: usize,
+    river: u32,
+    start: (usize, usize),
+) -> Option<(usize, usize)> {
+    let key = |h: f32| Reverse((h as f64 * 1e9) as i64);
+    let mut basin = vec![start];
+    let mut seen = HashSet::from([start]);
+    let mut rim = BinaryHeap::new();
+    let mut water_level = level[start].height;
+    for n in level.neighbours(start) {
+        seen.insert(n);
+        rim.push((key(level[n].height), n));
+    }
+    let mut outlet = None;
+    while let Some((_, p)) = rim.pop() {
+        let i = p.0 * SIZE + p.1;
+        let other_river = drainage.river[i] != NO_RIVER && drainage.river[i] != river;
+        if drainage.river[i] != river
+            && (level[p].height < water_level || level[p].tile.is_swimmable() || other_river)
+        {
+            outlet = Some(p);
+            break;
+        }
+        if basin.len() >= max_size {
+            break;
+        }
+        water_level = water_level.max(level[p].height);
+        basin.push(p);
+        for n in level.neighbours(p) {
+            if seen.insert(n) {
+                rim.push((key(level[n].height), n));
             }
         }
-        if dist as usize >= max_axis_distance {
-            break 'l None;
+    }
+    let downstream = outlet.map_or(NO_DOWNSTREAM, |(x, y)| (x * SIZE + y) as u32);
+    for p in basin {
+        let i = p.0 * SIZE + p.1;
+        // A flat surface, so the river doesn't run back in
+        level[p].height = water_level;
+        level[p].tile = Tile::OverworldWater;
+        drainage.lake[i] = true;
+        drainage.river[i] = river;
+        drainage.downstream[i] = downstream;
+    }
+    outlet
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SIZE: usize = 256;
+
+    #[test]
+    fn rivers_never_run_in_circles() {
+        let rivers = Rivers { sparseness: 8, max_length: 512, source_height: 0.3, max_lake_size: 256 };
+        for seed in [5344545, 1, 42, 777] {
+            let mut random = FastRandom::new(seed);
+            let mut level = Level::<SIZE>::new(Tile::OverworldDeepWater);
+            place_terrain(&mut level, &mut random, -0.1);
+            let drainage = trace_drainage(&mut level, &mut random, &rivers);
+            // Following a river downstream from anywhere has to end, in fewer steps than there are slots
+            for start in 0..SIZE * SIZE {
+                let mut j = start as u32;
+                let mut steps = 0;
+                while j != NO_DOWNSTREAM {
+                    j = drainage.downstream[j as usize];
+                    steps += 1;
+                    assert!(steps <= SIZE * SIZE, "seed {seed}: the river through {start} goes round in circles");
+                }
+            }
         }
-        dist += 1;
     }
-}
\ No newline at end of file
+}
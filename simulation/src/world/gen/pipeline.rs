@@ -0,0 +1,62 @@
+use crate::{world::{Level, Tile}, util::FastRandom};
+
+/// One pass of level generation, like placing terrain or carving tunnels
+pub trait LevelGenerator<const SIZE: usize> {
+    /// Also what the pass' random numbers are seeded from, so it should be unique within a pipeline
+    fn name(&self) -> &str;
+
+    fn generate(&self, level: &mut Level<SIZE>, random: &mut FastRandom);
+}
+
+/// The passes a level is generated with, run in order on a level filled with `base`
+pub struct LevelPipeline<const SIZE: usize> {
+    pub base: Tile,
+    pub open_sky: bool,
+    pub passes: Vec<Box<dyn LevelGenerator<SIZE>>>,
+}
+
+impl<const SIZE: usize> LevelPipeline<SIZE> {
+    pub fn new(base: Tile) -> Self {
+        Self {
+            base,
+            open_sky: false,
+            passes: Vec::new(),
+        }
+    }
+
+    pub fn under_open_sky(mut self) -> Self {
+        self.open_sky = true;
+        self
+    }
+
+    pub fn with(mut self, pass: impl LevelGenerator<SIZE> + 'static) -> Self {
+        self.passes.push(Box::new(pass));
+        self
+    }
+
+    /// Every pass gets its own random numbers, so adding or removing one doesn't change what the others do
+    pub fn run(&self, seed: u64, depth: usize) -> Level<SIZE> {
+        let mut level = Level::new(self.base);
+        level.open_sky = self.open_sky;
+        for pass in &self.passes {
+            let mut random = pass_random(seed, depth, pass.name());
+            pass.generate(&mut level, &mut random);
+        }
+        level
+    }
+}
+
+/// Seeded from the seed, the depth and the name
+fn pass_random(seed: u64, depth: usize, name: &str) -> FastRandom {
+    let bytes = seed.to_le_bytes().into_iter()
+        .chain((depth as u64).to_le_bytes())
+        .chain(name.bytes());
+    FastRandom::new(fnv1a(bytes))
+}
+
+/// FNV-1a, so what's hashed stays the same across builds and platforms
+pub(super) fn fnv1a(bytes: impl IntoIterator<Item = u8>) -> u64 {
+    const OFFSET: u64 = 0xcbf29ce484222325;
+    const PRIME: u64 = 0x100000001b3;
+    bytes.into_iter().fold(OFFSET, |hash, b| (hash ^ b as u64).wrapping_mul(PRIME))
+}
@@ -1,15 +1,7 @@
 
-use crate::{world::{Level, Slot, Tile}, util::FastRandom};
+use crate::{world::{Level, Tile, Room, RoomKind}, util::FastRandom};
 
-use super::{open_simplex_tileable_3d::OpenSimplexTileable3D, carve_from};
-
-#[derive(PartialEq, Eq, Debug, Copy, Clone)]
-pub struct Room {
-    left: usize,
-    top: usize,
-    right: usize,
-    bottom: usize,
-}
+use super::{carve_from, LevelGenerator, Noise2D, NoiseFields};
 
 impl Room {
     fn random_position_inside(&self, random: &mut FastRandom) -> (usize, usize) {
@@ -40,25 +32,55 @@ impl Room {
     }
 }
 
-pub(super) fn generate_underworld<const SIZE: usize>(seed: u64, quadrant_size: usize) -> (Level<SIZE>, Vec<Room>) {
-    let mut level = Level {
-        slots: vec![Slot {
-            tile: Tile::Void,
-            entity: None,
-        }; SIZE * SIZE].into_boxed_slice()
-    };
-    let mut random = FastRandom::new(seed - 4);
-    place_caves(&mut level, &mut random);
-    let rooms = place_dungeons(&mut level, &mut random, quadrant_size);
-    carve_worm_caves(&mut level, &mut random);
-    (level, rooms)
+/// Noisy caves carved out of the rock.
+/// The lower `min_wall_density`, the fewer caves, and the lower `humidity_scale`, the less grows in them
+pub struct Caves {
+    pub min_wall_density: f64,
+    pub humidity_scale: f64,
+}
+
+/// Rooms spread over quadrants of `quadrant_size` tiles, connected by corridors
+pub struct Dungeons {
+    pub quadrant_size: usize,
+}
+
+/// Tunnels from the rock to the closest floor, and between floors
+pub struct WormCaves {
+    pub sparseness: usize,
+}
+
+impl<const SIZE: usize> LevelGenerator<SIZE> for Caves {
+    fn name(&self) -> &str { "caves" }
+
+    fn generate(&self, level: &mut Level<SIZE>, random: &mut FastRandom) {
+        place_caves(level, random, self.min_wall_density, self.humidity_scale);
+    }
+}
+
+impl<const SIZE: usize> LevelGenerator<SIZE> for Dungeons {
+    fn name(&self) -> &str { "dungeons" }
+
+    fn generate(&self, level: &mut Level<SIZE>, random: &mut FastRandom) {
+        let (rooms, corridors) = place_dungeons(level, random, self.quadrant_size);
+        let offset = level.rooms.len();
+        level.rooms.extend(rooms);
+        level.corridors.extend(corridors.into_iter().map(|(a, b)| (a + offset, b + offset)));
+    }
+}
+
+impl<const SIZE: usize> LevelGenerator<SIZE> for WormCaves {
+    fn name(&self) -> &str { "worm caves" }
+
+    fn generate(&self, level: &mut Level<SIZE>, random: &mut FastRandom) {
+        carve_worm_caves(level, random, self.sparseness);
+    }
 }
 
 fn place_dungeons<const SIZE: usize>(
     level: &mut Level<SIZE>,
     random: &mut FastRandom,
     quadrant_size: usize,
-) -> Vec<Room> {
+) -> (Vec<Room>, Vec<(usize, usize)>) {
     let mut rooms = Vec::<Room>::new();
     let quadrants_in_side = SIZE / quadrant_size;
     for quadrant_x in 0..quadrants_in_side {
@@ -70,45 +92,52 @@ fn place_dungeons<const SIZE: usize>(
             }
         }
     }
-    connect_rooms(level, &rooms, random);
-    rooms
+    let corridors = connect_rooms(level, &rooms, random);
+    furnish_rooms(level, &mut rooms, random);
+    (rooms, corridors)
+}
+
+/// Gives every room a use. Shrines get an altar, the rest is filled in once there's something to fill them with.
+/// Only rooms at least 3 tiles wide and high inside can be shrines, so there's always a way around the altar.
+fn furnish_rooms<const SIZE: usize>(
+    level: &mut Level<SIZE>,
+    rooms: &mut [Room],
+    random: &mut FastRandom,
+) {
+    for room in rooms {
+        let roomy = room.right > room.left + 3 && room.bottom > room.top + 3;
+        room.kind = match random.next_less_than(8) {
+            0 | 1 => RoomKind::Lair,
+            2 => RoomKind::Storage,
+            3 if roomy => RoomKind::Shrine,
+            _ => RoomKind::Plain,
+        };
+        if room.kind == RoomKind::Shrine {
+            level[room.center()].tile = Tile::Altar;
+        }
+    }
 }
 
 fn place_caves<const SIZE: usize>(
     level: &mut Level<SIZE>,
     random: &mut FastRandom,
+    min_wall_density: f64,
+    humidity_scale: f64,
 ) {
-    let s = SIZE as i32 / 6;
-    let os32 = OpenSimplexTileable3D::new_with_seed_square(random.next() as i64, s / 64);
-    let os16 = OpenSimplexTileable3D::new_with_seed_square(random.next() as i64, s / 16);
-    let os8 = OpenSimplexTileable3D::new_with_seed_square(random.next() as i64, s / 8);
+    let fields = NoiseFields::new(random.next(), SIZE);
+    let walls = fields.warped_fbm("density", 32.0, 2, 0.25, 8.0);
+    let roughness = fields.layer("roughness", 32.0);
+    let detail = fields.fbm("detail", 16.0, 2, 0.67);
+    let humidity = fields.fbm("humidity", 32.0, 3, 0.35);
     for xi in 0..SIZE {
         for yi in 0..SIZE {
-            const MIN_WALL_DENSITY: f64 = -0.1;
-            const TEMPERATURE_BEACH_WEIGHT: f64 = 0.4;
             let (x, y) = (xi as f64, yi as f64);
-            let density_frequency = (os32.eval(x / 32.0, y / 32.0, 192.0) * 0.5 + 0.5).powi(2);
-            let inv_density_frequency = 1.0 - density_frequency;
-            let weights = [
-                1.0 * inv_density_frequency + 0.0 * density_frequency,
-                0.0 * inv_density_frequency + 0.6 * density_frequency,
-                0.0 * inv_density_frequency + 0.4 * density_frequency,
-            ];
-            let density = 0.1 + os32.eval(x / 32.0, y / 32.0, 0.0) * weights[0];
-            let density = if
-                density + weights[1] + weights[2] < MIN_WALL_DENSITY ||
-                density - weights[1] - weights[2] > TEMPERATURE_BEACH_WEIGHT {
-                    density
-                } else { density + os16.eval(x / 16.0, y / 16.0, 0.0) * weights[1] };
-            let density = if
-                density + weights[2] < MIN_WALL_DENSITY ||
-                density - weights[2] > TEMPERATURE_BEACH_WEIGHT {
-                    density
-                } else { density + os8.eval(x / 8.0, y / 8.0, 0.0) * weights[2] };
-
-            level[(xi, yi)].tile = if density < MIN_WALL_DENSITY {
-                let humidity = os32.eval(x / 32.0, y / 32.0, 256.0) * 0.5 + 0.5;
-                let humidity = 0.8 * humidity + 0.2 * (os8.eval(x / 8.0, y / 8.0, 256.0) * 0.5 + 0.5);
+            // Wide smooth caves in some places, broken up into little ones in others
+            let rough = (roughness.sample(x, y) * 0.5 + 0.5).powi(2);
+            let density = 0.07 + walls.sample(x, y) * (1.0 - rough) + detail.sample(x, y) * rough;
+            let humidity = (humidity.sample(x, y) * 0.5 + 0.5) * humidity_scale;
+            level[(xi, yi)].humidity = (humidity.clamp(0.0, 1.0) * 255.0) as u8;
+            level[(xi, yi)].tile = if density < min_wall_density {
                 if humidity > 0.7 {
                     Tile::Grass
                 } else if humidity > 0.5 {
@@ -126,21 +155,22 @@ fn place_caves<const SIZE: usize>(
 fn carve_worm_caves<const SIZE: usize>(
     level: &mut Level<SIZE>,
     random: &mut FastRandom,
+    sparseness: usize,
 ) {
-    let sparceness = 24;
-    for _ in 0..(SIZE / sparceness * SIZE / sparceness) {
+    for _ in 0..(SIZE / sparseness * SIZE / sparseness) {
         let (from_x, from_y) = find_void(random, level);
         let max_distance = 256;
         if let Some((to_x, to_y)) = closest_floor_tile(random, level, from_x, from_y, max_distance) {
-            carve_from(level, random, max_distance, from_x, from_y, to_x, to_y, Tile::Stone, true);
+            carve_from(level, random, max_distance, (from_x, from_y), (to_x, to_y), Tile::Stone);
         }
     }
-    let sparceness = 32;
-    for _ in 0..(SIZE / sparceness * SIZE / sparceness) {
+    // Fewer tunnels between floors than from the rock
+    let sparseness = sparseness * 4 / 3;
+    for _ in 0..(SIZE / sparseness * SIZE / sparseness) {
         let (from_x, from_y) = level.find_floor(random);
         let (to_x, to_y) = level.find_floor(random);
         let max_distance = 96;
-        carve_from(level, random, max_distance, from_x, from_y, to_x, to_y, Tile::Stone, true);
+        carve_from(level, random, max_distance, (from_x, from_y), (to_x, to_y), Tile::Stone);
     }
 }
 
@@ -151,7 +181,7 @@ fn find_void<const SIZE: usize>(
     loop {
         let (x, y) = (random.next_less_than(SIZE as u64), random.next_less_than(SIZE as u64));
         let c = (x as usize, y as usize);
-        let s = level[c];
+        let s = &level[c];
         if matches!(s.tile, Tile::Void) && s.entity.is_none() {
             return c;
         }
@@ -202,19 +232,21 @@ fn connect_rooms<const SIZE: usize>(
     level: &mut Level<SIZE>,
     rooms: &Vec<Room>,
     random: &mut FastRandom,
-) {
-    let mut rooms_to_connect: Vec<Room> = rooms.into_iter().map(|r| *r).collect();
+) -> Vec<(usize, usize)> {
+    let mut corridors = Vec::new();
+    let mut rooms_to_connect: Vec<usize> = (0..rooms.len()).collect();
     let mut i: isize = 0;
     while i < rooms_to_connect.len() as isize {
-        let room = rooms_to_connect[i as usize];
+        let room_index = rooms_to_connect[i as usize];
+        let room = rooms[room_index];
         let out_connections = random.next_in_range(1, 12) as usize;
         let mut closest_distance = 256;
-        let mut closest: Vec<&Room> = vec![];
-        for other_room in rooms {
-            let new_distance = Room::square_distance::<SIZE>(&room, &other_room);
+        let mut closest: Vec<usize> = vec![];
+        for (other_index, other_room) in rooms.iter().enumerate() {
+            let new_distance = Room::square_distance::<SIZE>(&room, other_room);
             if new_distance <= closest_distance {
                 closest_distance = new_distance;
-                closest.push(other_room);
+                closest.push(other_index);
                 if closest.len() >= out_connections {
                     closest.remove(0);
                 }
@@ -223,8 +255,8 @@ fn connect_rooms<const SIZE: usize>(
         closest.reverse();
         for ci in 0..closest.len() {
             if random.one_in((ci as i64 - 1).max(1) as u64) {
-                let other_room = closest[ci];
-                let a = rooms_to_connect.iter().position(|r| r == other_room);
+                let other_index = closest[ci];
+                let a = rooms_to_connect.iter().position(|r| *r == other_index);
                 match a {
                     Some(a) => {
                         rooms_to_connect.remove(a);
@@ -234,11 +266,15 @@ fn connect_rooms<const SIZE: usize>(
                     },
                     _ => {},
                 }
-                connect_room_pair(level, random, &room, other_room);
+                connect_room_pair(level, random, &room, &rooms[other_index]);
+                if other_index != room_index {
+                    corridors.push((room_index, other_index));
+                }
             }
         }
         i += 1;
     }
+    corridors
 }
 
 fn connect_room_pair<const SIZE: usize>(
@@ -252,20 +288,33 @@ fn connect_room_pair<const SIZE: usize>(
     let xr = if x0 < x1 { x0..x1 } else { x1..x0 };
     let yr = if y0 < y1 { y0..y1 } else { y1..y0 };
     for x in xr {
-        let c = (x % SIZE, y0 % SIZE);
-        if matches!(level[c].tile, Tile::Void) {
-            level[c].tile = Tile::Stone;
-        }
+        carve_corridor(level, (x % SIZE, y0 % SIZE), false);
     }
     for y in yr {
-        let c = (x1 % SIZE, y % SIZE);
-        if matches!(level[c].tile, Tile::Void) {
-            level[c].tile = Tile::Stone;
-        }
+        carve_corridor(level, (x1 % SIZE, y % SIZE), true);
     }
-    let c = (x1 % SIZE, y0 % SIZE);
-    if matches!(level[c].tile, Tile::Void) {
-        level[c].tile = Tile::Stone;
+    carve_corridor(level, (x1 % SIZE, y0 % SIZE), true);
+}
+
+/// Digs through the rock, and through walls with a doorway when going across them.
+/// Along a wall or at a corner there's no room for a door, so the wall is just opened up.
+fn carve_corridor<const SIZE: usize>(
+    level: &mut Level<SIZE>,
+    c: (usize, usize),
+    vertical: bool,
+) {
+    match level[c].tile {
+        Tile::Void => level[c].tile = Tile::Corridor,
+        Tile::DungeonWall => {
+            let (a, b) = if vertical {
+                (((c.0 + SIZE - 1) % SIZE, c.1), ((c.0 + 1) % SIZE, c.1))
+            } else {
+                ((c.0, (c.1 + SIZE - 1) % SIZE), (c.0, (c.1 + 1) % SIZE))
+            };
+            let across = level[a].tile == Tile::DungeonWall && level[b].tile == Tile::DungeonWall;
+            level[c].tile = if across { Tile::Doorway } else { Tile::DungeonFloor };
+        },
+        _ => {},
     }
 }
 
@@ -303,22 +352,22 @@ fn place_rooms<const SIZE: usize>(
         ) { return; }
 
         for x in left..=right {
-            level[(x as usize % SIZE, top as usize % SIZE)].tile = Tile::Void;
-            level[(x as usize % SIZE, bottom as usize % SIZE)].tile = Tile::Void;
+            level[(x as usize % SIZE, top as usize % SIZE)].tile = Tile::DungeonWall;
+            level[(x as usize % SIZE, bottom as usize % SIZE)].tile = Tile::DungeonWall;
         }
         for y in (top + 1)..bottom {
-            level[(left as usize % SIZE, y as usize % SIZE)].tile = Tile::Void;
-            level[(right as usize % SIZE, y as usize % SIZE)].tile = Tile::Void;
+            level[(left as usize % SIZE, y as usize % SIZE)].tile = Tile::DungeonWall;
+            level[(right as usize % SIZE, y as usize % SIZE)].tile = Tile::DungeonWall;
         }
 
         for x in (left + 1)..right {
             for y in (top + 1)..bottom {
                 let c = (x as usize % SIZE, y as usize % SIZE);
-                level[c].tile = Tile::Stone;
+                level[c].tile = Tile::DungeonFloor;
             }
         }
 
-        accumulator.push(Room { left, top, right, bottom });
+        accumulator.push(Room { left, top, right, bottom, kind: RoomKind::Plain });
     } else {
         if quadrant_height > quadrant_width || (quadrant_height == quadrant_width && random.one_in(2)) {
             let quadrant_height = quadrant_height / 2;
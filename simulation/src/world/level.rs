@@ -2,27 +2,114 @@ use std::ops::{Index, IndexMut};
 
 use crate::util::FastRandom;
 
-use super::{Tile, Entity};
+use super::{Tile, Entity, Scent, WaterFlow, Room};
 
-#[derive(Debug, Copy, Clone)]
+#[derive(Debug, Clone)]
 pub struct Slot {
     pub tile: Tile,
     pub entity: Option<Entity>,
+    /// How well plants grow here
+    pub humidity: u8,
+    /// How high the ground is, the sea being at 0
+    pub height: f32,
+    pub light: u8,
+    pub scent: Scent,
+    pub water: u8,
+}
+
+/// Something that changed in a level, for whoever keeps results worked out from it
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum LevelEvent {
+    TileChanged { position: (usize, usize), old: Tile },
+    LightChanged,
 }
 
 #[derive(Debug)]
 pub struct Level<const SIZE: usize> {
     pub slots: Box<[Slot]>,
+    /// What changed since someone last took the events
+    pub events: Vec<LevelEvent>,
+    /// Whether a tile changed in a way that affects the light
+    pub light_dirty: bool,
+    pub water_flow: WaterFlow,
+    /// Whether sunlight reaches the level
+    pub open_sky: bool,
+    /// How much light reaches the level from above
+    pub sky_light: u8,
+    pub rooms: Vec<Room>,
+    /// Pairs of indices in `rooms` connected by a corridor
+    pub corridors: Vec<(usize, usize)>,
 }
 
 impl<const SIZE: usize> Level<SIZE> {
+    pub fn new(tile: Tile) -> Self {
+        Self {
+            slots: vec![Slot {
+                tile,
+                entity: None,
+                humidity: 0,
+                height: 0.0,
+                light: 0,
+                scent: Scent::default(),
+                water: 0,
+            }; SIZE * SIZE].into_boxed_slice(),
+            events: Vec::new(),
+            light_dirty: false,
+            water_flow: WaterFlow::default(),
+            open_sky: false,
+            sky_light: 0,
+            rooms: Vec::new(),
+            corridors: Vec::new(),
+        }
+    }
+
+    /// Changes a tile after generation, letting everyone who cares know about it
+    pub fn set_tile(&mut self, position: (usize, usize), tile: Tile) {
+        let old = self[position].tile;
+        if old == tile {
+            return;
+        }
+        self[position].tile = tile;
+        if old.is_opaque() != tile.is_opaque() || old.light_emission() != tile.light_emission() {
+            self.light_dirty = true;
+        }
+        self.events.push(LevelEvent::TileChanged { position, old });
+        self.wake_water(position);
+    }
+
     pub fn swap_entities(&mut self, old: (usize, usize), new: (usize, usize)) {
-        let from_old = self[old].entity;
-        let from_new = self[new].entity;
+        let from_old = self[old].entity.take();
+        let from_new = self[new].entity.take();
         self[old].entity = from_new;
         self[new].entity = from_old;
     }
 
+    /// The 4 slots sharing an edge with `pos`, wrapping around the borders of the level
+    pub fn neighbours(&self, (x, y): (usize, usize)) -> [(usize, usize); 4] {
+        [
+            (x, (y + 1) % SIZE),
+            (x, (y + SIZE - 1) % SIZE),
+            ((x + 1) % SIZE, y),
+            ((x + SIZE - 1) % SIZE, y),
+        ]
+    }
+
+    /// Manhattan distance, going the short way around the borders
+    pub fn distance(&self, a: (usize, usize), b: (usize, usize)) -> usize {
+        let dx = a.0.abs_diff(b.0);
+        let dy = a.1.abs_diff(b.1);
+        dx.min(SIZE - dx) + dy.min(SIZE - dy)
+    }
+
+    /// The shortest way to get from `a` to `b` along each axis
+    pub fn offset(&self, a: (usize, usize), b: (usize, usize)) -> (i64, i64) {
+        let wrap = |d: i64| {
+            let d = d.rem_euclid(SIZE as i64);
+            if d > SIZE as i64 / 2 { d - SIZE as i64 } else { d }
+        };
+        (wrap(b.0 as i64 - a.0 as i64), wrap(b.1 as i64 - a.1 as i64))
+    }
+
     pub fn find_floor(&self, random: &mut FastRandom) -> (usize, usize) {
         loop {
             let (x, y) = (random.next_less_than(SIZE as u64), random.next_less_than(SIZE as u64));
@@ -0,0 +1,55 @@
+use std::collections::VecDeque;
+
+use super::{Level, LevelEvent, Tile};
+
+/// How bright it is under an open sky at noon
+pub const SUNLIGHT: u8 = 255;
+
+/// How much light is lost with every step away from its source
+const LIGHT_FALLOFF: u8 = 24;
+
+impl Tile {
+    /// How much light the tile gives off by itself
+    pub fn light_emission(&self) -> u8 {
+        match self {
+            Tile::StairsDown | Tile::StairsUp => 160,
+            Tile::Altar => 192,
+            Tile::GoldenTree => 128,
+            _ => 0,
+        }
+    }
+}
+
+impl<const SIZE: usize> Level<SIZE> {
+    /// Recalculates the light of every slot from the sky and the glowing tiles.
+    /// Light spreads to the neighbours, losing `LIGHT_FALLOFF` on every step, and doesn't go through opaque tiles.
+    pub fn update_light(&mut self) {
+        self.light_dirty = false;
+        self.events.push(LevelEvent::LightChanged);
+        let sky_light = self.sky_light;
+        let mut queue = VecDeque::new();
+        for x in 0..SIZE {
+            for y in 0..SIZE {
+                let slot = &mut self[(x, y)];
+                let emission = slot.tile.light_emission();
+                slot.light = sky_light.max(emission);
+                if emission > sky_light {
+                    queue.push_back((x, y));
+                }
+            }
+        }
+        while let Some(pos) = queue.pop_front() {
+            let light = self[pos].light.saturating_sub(LIGHT_FALLOFF);
+            if light == 0 || self[pos].tile.is_opaque() {
+                continue;
+            }
+            for n in self.neighbours(pos) {
+                let slot = &mut self[n];
+                if slot.light < light {
+                    slot.light = light;
+                    queue.push_back(n);
+                }
+            }
+        }
+    }
+}
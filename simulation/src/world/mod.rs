@@ -1,19 +1,33 @@
 mod ambient;
+mod clock;
 mod entity;
-mod gen;
+pub mod gen;
 mod level;
+mod light;
+mod pathfinding;
+mod portal;
+mod regions;
+mod room;
+mod scent;
 mod slot_position;
 mod tile;
+mod water;
 
 use std::ops::{Index, IndexMut};
 
 use self::creature::SpeciesMap;
-pub use self::{ambient::*, tile::*, entity::*, level::*, slot_position::*};
+pub use self::{ambient::*, clock::*, tile::*, entity::*, level::*, light::*, portal::*, regions::*, room::*, scent::*, slot_position::*, water::*};
+
+/// Changes in sunlight smaller than this don't cause the light to be recalculated
+const SUNLIGHT_STEP: u8 = 16;
 
 pub struct World<const SIZE: usize, const H: usize> {
     pub levels: [Level<SIZE>; H],
     pub player_position: SlotPosition,
     pub species: SpeciesMap,
+    pub items: ItemMap,
+    pub portals: PortalMap,
+    pub clock: Clock,
 }
 
 impl<const SIZE: usize, const H: usize> World<SIZE, H> {
@@ -22,9 +36,30 @@ impl<const SIZE: usize, const H: usize> World<SIZE, H> {
         let y = (self.player_position.y as isize + y as isize + SIZE as isize) as usize % SIZE;
         SlotPosition { x, y, level: self.player_position.level }
     }
+    /// Lets a tick pass, relighting the levels under the open sky whenever the sunlight changes enough to matter
+    pub fn advance_clock(&mut self) {
+        let before = self.clock.sunlight() / SUNLIGHT_STEP;
+        self.clock.advance();
+        if self.clock.sunlight() / SUNLIGHT_STEP != before {
+            let sunlight = self.clock.sunlight();
+            for level in self.levels.iter_mut().filter(|l| l.open_sky) {
+                level.sky_light = sunlight;
+                level.update_light();
+            }
+        }
+    }
+
+    /// Everything that changed in each level since the last time, by level index
+    pub fn take_events(&mut self) -> Vec<(usize, LevelEvent)> {
+        self.levels.iter_mut()
+            .enumerate()
+            .flat_map(|(l, level)| level.events.drain(..).map(move |e| (l, e)))
+            .collect()
+    }
+
     pub fn swap_entities(&mut self, old: SlotPosition, new: SlotPosition) {
-        let from_old = self[&old].entity;
-        let from_new = self[&new].entity;
+        let from_old = self[&old].entity.take();
+        let from_new = self[&new].entity.take();
         self[&old].entity = from_new;
         self[&new].entity = from_old;
     }
@@ -0,0 +1,71 @@
+use std::{collections::{BinaryHeap, HashMap}, cmp::Reverse};
+
+use super::Level;
+
+impl<const SIZE: usize> Level<SIZE> {
+    pub fn is_passable(&self, pos: (usize, usize), can_swim: bool) -> bool {
+        let tile = &self[pos].tile;
+        tile.is_floor() || (can_swim && tile.is_swimmable())
+    }
+
+    /// A* search over the tiles, ignoring entities since they move around anyway.
+    /// The steps are returned in reverse order, so the next one can be popped off the end,
+    /// and `from` isn't included. Gives up after expanding `max_expanded` slots.
+    pub fn find_path(
+        &self,
+        from: (usize, usize),
+        to: (usize, usize),
+        can_swim: bool,
+        max_expanded: usize,
+    ) -> Option<Vec<(usize, usize)>> {
+        if from == to {
+            return Some(vec![]);
+        }
+        if !self.is_passable(to, can_swim) {
+            return None;
+        }
+        let index = |(x, y): (usize, usize)| x * SIZE + y;
+        let position = |i: usize| (i / SIZE, i % SIZE);
+        let goal = index(to);
+
+        let mut open = BinaryHeap::new();
+        let mut came_from = HashMap::<usize, usize>::new();
+        let mut cost = HashMap::<usize, usize>::new();
+        open.push(Reverse((self.distance(from, to), 0, index(from))));
+        cost.insert(index(from), 0);
+
+        let mut expanded = 0;
+        while let Some(Reverse((_, g, i))) = open.pop() {
+            if i == goal {
+                let mut path = vec![to];
+                let mut i = goal;
+                while let Some(&parent) = came_from.get(&i) {
+                    i = parent;
+                    path.push(position(i));
+                }
+                path.pop();
+                return Some(path);
+            }
+            if g > cost[&i] {
+                continue;
+            }
+            expanded += 1;
+            if expanded > max_expanded {
+                return None;
+            }
+            for n in self.neighbours(position(i)) {
+                if !self.is_passable(n, can_swim) {
+                    continue;
+                }
+                let ni = index(n);
+                let ng = g + 1;
+                if cost.get(&ni).is_none_or(|&c| ng < c) {
+                    cost.insert(ni, ng);
+                    came_from.insert(ni, i);
+                    open.push(Reverse((ng + self.distance(n, to), ng, ni)));
+                }
+            }
+        }
+        None
+    }
+}
@@ -0,0 +1,52 @@
+use std::collections::HashMap;
+
+use super::{World, SlotPosition};
+
+/// How far from the stairs one can end up when something's standing on them
+const ARRIVAL_RADIUS: usize = 3;
+
+/// Where every stairs leads, both ways
+pub type PortalMap = HashMap<SlotPosition, SlotPosition>;
+
+impl<const SIZE: usize, const H: usize> World<SIZE, H> {
+    /// Where taking the stairs at `from` leads, if they lead anywhere
+    pub fn portal_destination(&self, from: &SlotPosition) -> Option<SlotPosition> {
+        self.portals.get(from).copied()
+    }
+
+    /// The stairs at `destination` if they're free, or else the closest free floor around them
+    pub fn arrival_slot(&self, destination: &SlotPosition) -> Option<SlotPosition> {
+        let level = &self.levels[destination.level];
+        let free = |p: (usize, usize)| level[p].tile.is_floor() && level[p].entity.is_none();
+        let (x, y) = (destination.x, destination.y);
+        if free((x, y)) {
+            return Some(*destination);
+        }
+        for dist in 1..=ARRIVAL_RADIUS as i64 {
+            for dx in -dist..=dist {
+                for dy in -dist..=dist {
+                    if dx.abs() != dist && dy.abs() != dist {
+                        continue;
+                    }
+                    let p = (
+                        (x as i64 + dx + SIZE as i64) as usize % SIZE,
+                        (y as i64 + dy + SIZE as i64) as usize % SIZE,
+                    );
+                    if free(p) {
+                        return Some(SlotPosition { x: p.0, y: p.1, level: destination.level });
+                    }
+                }
+            }
+        }
+        None
+    }
+
+    /// Moves whatever's on the stairs at `from` to where they lead, without pushing anyone out of the way.
+    /// Returns where it arrived, or nothing if there's nowhere to arrive.
+    pub fn take_portal(&mut self, from: &SlotPosition) -> Option<SlotPosition> {
+        let arrival = self.portal_destination(from).and_then(|d| self.arrival_slot(&d))?;
+        let entity = self[from].entity.take();
+        self[&arrival].entity = entity;
+        Some(arrival)
+    }
+}
@@ -0,0 +1,77 @@
+use super::Level;
+
+/// Slots that aren't in any region
+const NO_REGION: u32 = u32::MAX;
+
+/// The parts of a level that can be walked between without swimming
+pub struct Regions<const SIZE: usize> {
+    ids: Box<[u32]>,
+    sizes: Vec<usize>,
+}
+
+impl<const SIZE: usize> Regions<SIZE> {
+    pub fn region_at(&self, (x, y): (usize, usize)) -> Option<usize> {
+        match self.ids[x * SIZE + y] {
+            NO_REGION => None,
+            id => Some(id as usize),
+        }
+    }
+
+    /// How many slots are in `region`
+    pub fn size(&self, region: usize) -> usize {
+        self.sizes[region]
+    }
+
+    pub fn count(&self) -> usize {
+        self.sizes.len()
+    }
+
+    /// The region with the most slots, the first one found if there's a tie
+    pub fn largest(&self) -> Option<usize> {
+        (0..self.sizes.len()).rev().max_by_key(|r| self.sizes[*r])
+    }
+
+    /// Whether one can walk from `a` to `b`
+    pub fn is_connected(&self, a: (usize, usize), b: (usize, usize)) -> bool {
+        self.region_at(a).is_some() && self.region_at(a) == self.region_at(b)
+    }
+
+    /// Every slot in `region`
+    pub fn positions(&self, region: usize) -> impl Iterator<Item = (usize, usize)> + '_ {
+        self.ids.iter()
+            .enumerate()
+            .filter(move |(_, id)| **id as usize == region)
+            .map(|(i, _)| (i / SIZE, i % SIZE))
+    }
+}
+
+impl<const SIZE: usize> Level<SIZE> {
+    /// Flood fills the floor, giving every part that can't be walked to from the others its own region
+    pub fn regions(&self) -> Regions<SIZE> {
+        let mut ids = vec![NO_REGION; SIZE * SIZE].into_boxed_slice();
+        let mut sizes = Vec::new();
+        let mut stack = Vec::new();
+        for start in 0..SIZE * SIZE {
+            let start_pos = (start / SIZE, start % SIZE);
+            if ids[start] != NO_REGION || !self.is_passable(start_pos, false) {
+                continue;
+            }
+            let id = sizes.len() as u32;
+            let mut size = 0;
+            ids[start] = id;
+            stack.push(start_pos);
+            while let Some(pos) = stack.pop() {
+                size += 1;
+                for n in self.neighbours(pos) {
+                    let i = n.0 * SIZE + n.1;
+                    if ids[i] == NO_REGION && self.is_passable(n, false) {
+                        ids[i] = id;
+                        stack.push(n);
+                    }
+                }
+            }
+            sizes.push(size);
+        }
+        Regions { ids, sizes }
+    }
+}
@@ -0,0 +1,62 @@
+use std::fmt::Display;
+
+use super::Level;
+
+/// What a dungeon room is used for, which decides what ends up in it
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum RoomKind {
+    Plain,
+    /// Where creatures nest
+    Lair,
+    /// Full of stuff
+    Storage,
+    /// Lit by an altar
+    Shrine,
+    /// Has stairs to another level
+    Entrance,
+}
+
+impl Display for RoomKind {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", match self {
+            RoomKind::Plain => "room",
+            RoomKind::Lair => "lair",
+            RoomKind::Storage => "storage room",
+            RoomKind::Shrine => "shrine",
+            RoomKind::Entrance => "stairwell",
+        })
+    }
+}
+
+/// A dungeon room, walls included in its bounds
+#[derive(PartialEq, Eq, Debug, Copy, Clone)]
+pub struct Room {
+    pub left: usize,
+    pub top: usize,
+    pub right: usize,
+    pub bottom: usize,
+    pub kind: RoomKind,
+}
+
+impl Room {
+    /// Whether `(x, y)` is between the walls
+    pub fn contains(&self, (x, y): (usize, usize)) -> bool {
+        x > self.left && x < self.right && y > self.top && y < self.bottom
+    }
+
+    pub fn center(&self) -> (usize, usize) {
+        ((self.left + self.right) / 2, (self.top + self.bottom) / 2)
+    }
+
+    /// Every position between the walls
+    pub fn inside(&self) -> impl Iterator<Item = (usize, usize)> {
+        let (top, bottom) = (self.top, self.bottom);
+        ((self.left + 1)..self.right).flat_map(move |x| ((top + 1)..bottom).map(move |y| (x, y)))
+    }
+}
+
+impl<const SIZE: usize> Level<SIZE> {
+    pub fn room_at(&self, position: (usize, usize)) -> Option<&Room> {
+        self.rooms.iter().find(|r| r.contains(position))
+    }
+}
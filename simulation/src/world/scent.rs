@@ -0,0 +1,38 @@
+use super::Level;
+
+/// Scent lost by every slot each tick, on top of what spreads to the neighbours
+const SCENT_DECAY: u8 = 1;
+
+/// What's been around lately, kept apart for plant eaters and meat eaters
+#[derive(Debug, Copy, Clone, Default, PartialEq, Eq)]
+pub struct Scent {
+    pub prey: u8,
+    pub predator: u8,
+}
+
+impl<const SIZE: usize> Level<SIZE> {
+    /// Every slot keeps half of its scent and passes an eighth to each neighbour, then some of it fades away.
+    /// Scent doesn't get into opaque tiles.
+    pub fn diffuse_scent(&mut self) {
+        let old: Vec<Scent> = self.slots.iter().map(|s| s.scent).collect();
+        let at = |(x, y): (usize, usize)| old[x * SIZE + y];
+        for x in 0..SIZE {
+            for y in 0..SIZE {
+                if self[(x, y)].tile.is_opaque() {
+                    self[(x, y)].scent = Scent::default();
+                    continue;
+                }
+                let here = at((x, y));
+                let (mut prey, mut predator) = (here.prey as u16 * 4, here.predator as u16 * 4);
+                for n in self.neighbours((x, y)) {
+                    prey += at(n).prey as u16;
+                    predator += at(n).predator as u16;
+                }
+                self[(x, y)].scent = Scent {
+                    prey: ((prey / 8) as u8).saturating_sub(SCENT_DECAY),
+                    predator: ((predator / 8) as u8).saturating_sub(SCENT_DECAY),
+                };
+            }
+        }
+    }
+}
@@ -1,6 +1,6 @@
 
 
-#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+#[derive(Debug, PartialEq, Eq, Hash, Clone, Copy)]
 pub struct SlotPosition {
     pub x: usize,
     pub y: usize,
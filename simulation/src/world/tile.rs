@@ -1,8 +1,10 @@
 
 #[derive(Debug, Copy, Clone, PartialEq, Eq)]
 pub enum Tile {
-    GotoUnderworld,
-    GotoOverworld,
+    /// Leads to the stairs up it's linked with, one level deeper
+    StairsDown,
+    /// Leads to the stairs down it's linked with, one level higher up
+    StairsUp,
 
     Void,
 
@@ -10,6 +12,10 @@ pub enum Tile {
     OverworldScraps,
     OverworldWater,
     OverworldDeepWater,
+    Sand,
+    Snow,
+    /// Dug into the ground, where water can run
+    Trench,
 
     Stone,
     ThinGrass,
@@ -27,6 +33,15 @@ pub enum Tile {
     
     DungeonFloor,
     DungeonWall,
+    /// Where a corridor goes through a dungeon wall
+    Doorway,
+    /// Dug between dungeon rooms
+    Corridor,
+    /// Shining in the middle of shrines
+    Altar,
+
+    /// Built from stones
+    StoneWall,
 
     // Water
     SeaWeed,
@@ -37,7 +52,7 @@ pub enum Tile {
 impl Tile {
     pub fn is_floor(&self) -> bool {
         !matches!(self,
-            Tile::Void | Tile::DungeonWall |
+            Tile::Void | Tile::DungeonWall | Tile::StoneWall | Tile::Altar |
             Tile::DeepWater | Tile::Water |
             Tile::OverworldDeepWater | Tile::OverworldWater |
             Tile::AcaciaTree | Tile::BirchTree | Tile::GoldenTree | Tile::OakTree | Tile::PalmTree | Tile::PeachTree | Tile::SpruceTree)
@@ -60,6 +75,64 @@ impl Tile {
         matches!(self, Tile::Stone | Tile::ThinGrass)
     }
     pub fn is_opaque(&self) -> bool {
-        matches!(self, Tile::Void)
+        matches!(self, Tile::Void | Tile::StoneWall | Tile::DungeonWall)
+    }
+
+    /// What's left once it's been dug through, if it can be
+    pub fn dug(&self) -> Option<Tile> {
+        match self {
+            Tile::Void | Tile::StoneWall => Some(Tile::Stone),
+            Tile::DungeonWall => Some(Tile::DungeonFloor),
+            Tile::OverworldGravel | Tile::OverworldScraps | Tile::Sand | Tile::Snow => Some(Tile::Trench),
+            _ => None,
+        }
+    }
+
+    /// Whether a wall can be built here
+    pub fn is_buildable(&self) -> bool {
+        self.is_floor() && !self.is_stairs()
+    }
+
+    pub fn is_stairs(&self) -> bool {
+        matches!(self, Tile::StairsDown | Tile::StairsUp)
+    }
+
+    /// Which level the stairs lead to when taken from `level`
+    pub fn stairs_target(&self, level: usize) -> Option<usize> {
+        match self {
+            Tile::StairsDown => Some(level + 1),
+            Tile::StairsUp => level.checked_sub(1),
+            _ => None,
+        }
+    }
+
+    /// The name of the item that falls off this tree, if any
+    pub fn fruit(&self) -> Option<&'static str> {
+        match self {
+            Tile::PeachTree => Some("peach"),
+            _ => None,
+        }
+    }
+
+    /// What's left after a plant eater had a bite. Under the open sky that's bare gravel, underground it's stone.
+    pub fn grazed(&self, open_sky: bool) -> Option<Tile> {
+        match self {
+            Tile::Grass => Some(Tile::ThinGrass),
+            Tile::ThinGrass if open_sky => Some(Tile::OverworldGravel),
+            Tile::ThinGrass => Some(Tile::Stone),
+            _ => None,
+        }
+    }
+
+    /// What grows back here given enough humidity, the same thresholds as during generation
+    pub fn regrown(&self, humidity: u8, open_sky: bool) -> Option<Tile> {
+        match (self, open_sky) {
+            // Meadows and savannas are anything wetter than dry, forests with all their grass only the humid parts
+            (Tile::OverworldGravel, true) if humidity > 102 => Some(Tile::ThinGrass),
+            (Tile::ThinGrass, true) if humidity > 153 => Some(Tile::Grass),
+            (Tile::Stone, false) if humidity > 127 => Some(Tile::ThinGrass),
+            (Tile::ThinGrass, false) if humidity > 178 => Some(Tile::Grass),
+            _ => None,
+        }
     }
 }
\ No newline at end of file
@@ -0,0 +1,161 @@
+use std::collections::{HashMap, HashSet, VecDeque};
+
+use crate::util::FastRandom;
+
+use super::{Level, SlotPosition, Tile, World};
+
+/// Water this deep or deeper shows as deep water
+const DEEP_WATER: u8 = 128;
+
+/// Less water than this only makes the ground wet, it takes this much to flood it
+const FLOODING_WATER: u8 = 32;
+
+/// How much water the generated shallow water holds
+const SHALLOW_WATER: u8 = 96;
+
+/// Slots whose water gets updated per level every tick, the rest waits for the next ones
+const WATER_UPDATES_PER_TICK: usize = 1024;
+
+/// One in how many slots get a chance to lose some water every tick
+const EVAPORATION_SPARSENESS: usize = 64;
+
+/// One in how many times a flooded slot that gets the chance actually loses some water
+const EVAPORATION_CHANCE: u64 = 4;
+
+/// Where water is on the move, for the simulation to only look at what may change
+#[derive(Debug, Default)]
+pub struct WaterFlow {
+    pub queue: VecDeque<(usize, usize)>,
+    pub queued: HashSet<(usize, usize)>,
+    /// What each flooded slot was before the water got there
+    pub flooded: HashMap<(usize, usize), Tile>,
+}
+
+impl<const SIZE: usize> Level<SIZE> {
+    /// Gives the generated water tiles their water, which is where all water comes from
+    pub fn fill_water(&mut self) {
+        for slot in self.slots.iter_mut() {
+            slot.water = match slot.tile {
+                Tile::DeepWater | Tile::OverworldDeepWater => u8::MAX,
+                Tile::Water | Tile::OverworldWater | Tile::SeaWeed => SHALLOW_WATER,
+                _ => 0,
+            };
+        }
+    }
+
+    /// Under the open sky water only gets into trenches and down portals, underground it fills any floor
+    pub fn holds_water(&self, pos: (usize, usize)) -> bool {
+        let tile = self[pos].tile;
+        tile.is_swimmable() || matches!(tile, Tile::Trench | Tile::StairsDown | Tile::SeaWeed) ||
+            (!self.open_sky && tile.is_floor())
+    }
+
+    /// Generated water never runs out
+    pub fn is_water_source(&self, pos: (usize, usize)) -> bool {
+        self[pos].water != 0 && (self[pos].tile.is_swimmable() || self[pos].tile == Tile::SeaWeed) &&
+            !self.water_flow.flooded.contains_key(&pos)
+    }
+
+    /// Sets how much water a slot holds, flooding it or giving it back its tile as needed
+    pub fn set_water(&mut self, pos: (usize, usize), water: u8) {
+        self[pos].water = water;
+        let tile = self[pos].tile;
+        if matches!(tile, Tile::StairsDown | Tile::StairsUp) {
+            return;
+        }
+        if water < FLOODING_WATER {
+            if let Some(original) = self.water_flow.flooded.remove(&pos) {
+                self.set_tile(pos, original);
+            }
+            return;
+        }
+        self.water_flow.flooded.entry(pos).or_insert(tile);
+        let deep = water >= DEEP_WATER;
+        self.set_tile(pos, match (self.open_sky, deep) {
+            (true, true) => Tile::OverworldDeepWater,
+            (true, false) => Tile::OverworldWater,
+            (false, true) => Tile::DeepWater,
+            (false, false) => Tile::Water,
+        });
+    }
+
+    /// Lets the water in and around `pos` move again
+    pub fn wake_water(&mut self, pos: (usize, usize)) {
+        for p in std::iter::once(pos).chain(self.neighbours(pos)) {
+            if self.water_flow.queued.insert(p) {
+                self.water_flow.queue.push_back(p);
+            }
+        }
+    }
+
+    /// Water that flooded somewhere slowly dries up, unless there's more where it came from
+    fn evaporate(&mut self, random: &mut FastRandom) {
+        for _ in 0..(SIZE * SIZE / EVAPORATION_SPARSENESS) {
+            let pos = (random.next_less_than(SIZE as u64) as usize, random.next_less_than(SIZE as u64) as usize);
+            let water = self[pos].water;
+            if water != 0 && !self.is_water_source(pos) && random.one_in(EVAPORATION_CHANCE) {
+                self.set_water(pos, water - 1);
+                self.wake_water(pos);
+            }
+        }
+    }
+}
+
+impl<const SIZE: usize, const H: usize> World<SIZE, H> {
+    /// Water evens out with its neighbours, a quarter of the difference at a time, and falls down portals.
+    /// Only the slots where something changed get looked at, and only so many of them per tick.
+    pub fn flow_water(&mut self, random: &mut FastRandom) {
+        for l in 0..H {
+            self.levels[l].evaporate(random);
+            for _ in 0..WATER_UPDATES_PER_TICK {
+                let pos = match self.levels[l].water_flow.queue.pop_front() {
+                    Some(pos) => pos,
+                    None => break,
+                };
+                self.levels[l].water_flow.queued.remove(&pos);
+                if self.levels[l][pos].water == 0 {
+                    continue;
+                }
+                if self.levels[l][pos].tile == Tile::StairsDown {
+                    self.fall(l, pos);
+                }
+                let level = &mut self.levels[l];
+                for n in level.neighbours(pos) {
+                    let (water, other) = (level[pos].water, level[n].water);
+                    if water < other.saturating_add(2) || !level.holds_water(n) || level.is_water_source(n) {
+                        continue;
+                    }
+                    // Or the level below would fill up with no end
+                    if level.is_water_source(pos) && level[n].tile.is_stairs() {
+                        continue;
+                    }
+                    let flow = ((water - other) / 4).max(1);
+                    level.set_water(n, other + flow);
+                    level.wake_water(n);
+                    if !level.is_water_source(pos) {
+                        level.set_water(pos, water - flow);
+                        level.wake_water(pos);
+                    }
+                }
+            }
+        }
+    }
+
+    /// Water on a portal going down pours onto the stairs up it leads to
+    fn fall(&mut self, l: usize, pos: (usize, usize)) {
+        let to = match self.portal_destination(&SlotPosition { x: pos.0, y: pos.1, level: l }) {
+            Some(to) => to,
+            None => return,
+        };
+        let landing = (to.x, to.y);
+        let (water, below) = (self.levels[l][pos].water, self.levels[to.level][landing].water);
+        let flow = (water / 4).max(1).min(u8::MAX - below);
+        if flow == 0 {
+            return;
+        }
+        self.levels[l].set_water(pos, water - flow);
+        self.levels[l].wake_water(pos);
+        self.levels[to.level].set_water(landing, below + flow);
+        self.levels[to.level].wake_water(landing);
+    }
+}
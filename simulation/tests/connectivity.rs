@@ -0,0 +1,133 @@
+use simulation::util::FastRandom;
+use simulation::world::{World, Entity, Tile, SlotPosition, gen::WorldGenConfig, creature::{SpeciesTemplate, SpeciesMap, SpeciesID, Diet}};
+
+const SIZE: usize = 384;
+const LEVELS: usize = 4;
+const SEEDS: [u64; 6] = [5344545, 1, 42, 777, 123456789, 987654321];
+
+fn species(name: &str) -> SpeciesTemplate {
+    SpeciesTemplate {
+        name: name.to_string(),
+        symbol: 'g',
+        diet: Diet { meat: false, plants: true, light: false },
+        max_health: 255,
+        max_nutrition: 255,
+        smelliness: 128,
+        strength: 40,
+        speed: 128,
+        can_dig: false,
+        awareness: 128,
+        curiosity: 128,
+        friendliness: 0,
+        nocturnal: false,
+        relations: Default::default(),
+    }
+}
+
+fn generate(seed: u64) -> World<SIZE, LEVELS> {
+    let config = WorldGenConfig::default();
+    let mut world = World::generate(seed, &config);
+    let mut species_map = SpeciesMap::new();
+    species_map.insert(SpeciesID(0), species("gunfus"));
+    species_map.insert(SpeciesID(1), species("Player"));
+    world.create_life(seed, &config, species_map, SpeciesID(1));
+    world
+}
+
+/// Everything under the surface can be walked to, and stairs, creatures and the player are never in a pocket
+fn check_reachability(seed: u64, world: &World<SIZE, LEVELS>) {
+    for (l, level) in world.levels.iter().enumerate() {
+        let regions = level.regions();
+        let main = regions.largest();
+        assert!(main.is_some(), "seed {seed}: level {l} has no floor");
+        if l > 0 {
+            assert_eq!(regions.count(), 1, "seed {seed}: level {l} has unreachable pockets");
+        }
+        for x in 0..SIZE {
+            for y in 0..SIZE {
+                let slot = &level[(x, y)];
+                if slot.tile.is_stairs() {
+                    assert_eq!(regions.region_at((x, y)), main, "seed {seed}: stairs at {x}, {y} on level {l} lead to a pocket");
+                }
+                if matches!(slot.entity, Some(Entity::Creature(_))) {
+                    assert_eq!(regions.region_at((x, y)), main, "seed {seed}: creature at {x}, {y} on level {l} spawned in a pocket");
+                }
+            }
+        }
+    }
+    let player = world.player_position;
+    let regions = world.levels[player.level].regions();
+    assert_eq!(regions.region_at((player.x, player.y)), regions.largest(), "seed {seed}: player spawned in a pocket");
+}
+
+/// Every pair of adjacent levels has stairs, each going down to stairs going up, and linked to them both ways
+fn check_stairs(seed: u64, world: &World<SIZE, LEVELS>) {
+    for (l, level) in world.levels.iter().enumerate() {
+        for (i, slot) in level.slots.iter().enumerate() {
+            if !slot.tile.is_stairs() {
+                continue;
+            }
+            let from = SlotPosition { x: i / SIZE, y: i % SIZE, level: l };
+            let to = world.portal_destination(&from);
+            assert!(to.is_some(), "seed {seed}: stairs at {from:?} aren't linked");
+            let to = to.unwrap();
+            assert_eq!(Some(to.level), slot.tile.stairs_target(l), "seed {seed}: stairs at {from:?} lead to the wrong level");
+            assert!(world[&to].tile.is_stairs(), "seed {seed}: stairs at {from:?} lead to {:?}", world[&to].tile);
+            assert_eq!(world.portal_destination(&to), Some(from), "seed {seed}: stairs at {to:?} don't lead back to {from:?}");
+        }
+    }
+    for l in 1..LEVELS {
+        let links: Vec<_> = (0..SIZE * SIZE)
+            .map(|i| SlotPosition { x: i / SIZE, y: i % SIZE, level: l - 1 })
+            .filter(|p| world[p].tile == Tile::StairsDown)
+            .map(|p| (p, world.portal_destination(&p).unwrap()))
+            .inspect(|(p, to)| assert_eq!(world[to].tile, Tile::StairsUp, "seed {seed}: stairs down at {p:?} lead to {:?}", world[to].tile))
+            .collect();
+        assert!(!links.is_empty(), "seed {seed}: nothing links level {} with level {l}", l - 1);
+        // Each level's stairs are placed on their own, not where there happens to be floor on both
+        assert!(links.iter().any(|(p, to)| (p.x, p.y) != (to.x, to.y)), "seed {seed}: stairs from level {} all lead straight down", l - 1);
+    }
+}
+
+#[test]
+fn generated_worlds_are_connected() {
+    for seed in SEEDS {
+        let world = generate(seed);
+        check_reachability(seed, &world);
+        check_stairs(seed, &world);
+    }
+}
+
+/// Water spreading from the seas and rivers, down the stairs too, never cuts stairs off
+#[test]
+fn stairs_stay_reachable_as_water_flows() {
+    const TICKS: usize = 2000;
+    for seed in [5344545, 42] {
+        let mut world = generate(seed);
+        let mut random = FastRandom::new(seed);
+        // Water only starts moving when something changes near it, so it's stirred around every stairs to begin with
+        for level in world.levels.iter_mut() {
+            for i in 0..SIZE * SIZE {
+                let p = (i / SIZE, i % SIZE);
+                if level[p].tile.is_stairs() {
+                    for n in level.neighbours(p) {
+                        level.wake_water(n);
+                    }
+                }
+            }
+        }
+        for _ in 0..TICKS {
+            world.flow_water(&mut random);
+        }
+        for (l, level) in world.levels.iter().enumerate() {
+            let regions = level.regions();
+            for x in 0..SIZE {
+                for y in 0..SIZE {
+                    if level[(x, y)].tile.is_stairs() {
+                        assert_eq!(regions.region_at((x, y)), regions.largest(), "seed {seed}: stairs at {x}, {y} on level {l} got cut off by water");
+                    }
+                }
+            }
+        }
+    }
+}
@@ -0,0 +1,90 @@
+use simulation::world::gen::{Noise2D, NoiseLayer, SimplexTileable2D, SimplexTileable4D};
+
+const SEEDS: [u64; 3] = [5344545, 1, 42];
+
+/// Points spread over a few periods, off the lattice so they don't all land on corners
+fn points(count: usize, spread: f64) -> impl Iterator<Item = (f64, f64)> {
+    (0..count).flat_map(move |i| (0..count).map(move |j| {
+        (i as f64 * spread / count as f64 + 0.123, j as f64 * spread / count as f64 + 0.456)
+    }))
+}
+
+#[test]
+fn simplex_2d_tiles() {
+    for seed in SEEDS {
+        let noise = SimplexTileable2D::new(seed, 12, 9);
+        for (x, y) in points(40, 24.0) {
+            let value = noise.eval(x, y);
+            assert!((value - noise.eval(x + 12.0, y)).abs() < 1e-9, "({x}, {y}) doesn't repeat along x");
+            assert!((value - noise.eval(x, y + 9.0)).abs() < 1e-9, "({x}, {y}) doesn't repeat along y");
+            assert!((value - noise.eval(x - 24.0, y - 18.0)).abs() < 1e-9, "({x}, {y}) doesn't repeat backwards");
+        }
+    }
+}
+
+#[test]
+fn noise_layer_tiles_like_the_level() {
+    const SIZE: usize = 384;
+    for seed in SEEDS {
+        for feature_size in [7.0, 16.0, 64.0] {
+            let layer = NoiseLayer::new(seed, SIZE, feature_size);
+            for (x, y) in points(40, SIZE as f64) {
+                let value = layer.sample(x, y);
+                assert!((value - layer.sample(x + SIZE as f64, y)).abs() < 1e-9);
+                assert!((value - layer.sample(x, y + SIZE as f64)).abs() < 1e-9);
+            }
+        }
+    }
+}
+
+#[test]
+fn simplex_4d_tiles() {
+    let noise = SimplexTileable4D::new(5344545, [6, 6, 3, 0]);
+    for (x, y) in points(30, 12.0) {
+        let (z, w) = (y * 0.5 + 0.3, x * 0.25 - 0.7);
+        let value = noise.eval(x, y, z, w);
+        assert!((value - noise.eval(x + 6.0, y, z, w)).abs() < 1e-9);
+        assert!((value - noise.eval(x, y + 6.0, z, w)).abs() < 1e-9);
+        assert!((value - noise.eval(x, y, z + 3.0, w)).abs() < 1e-9);
+    }
+}
+
+#[test]
+fn simplex_stays_in_range() {
+    for seed in SEEDS {
+        let noise_2d = SimplexTileable2D::new(seed, 30, 30);
+        let (min, max) = points(300, 30.0)
+            .map(|(x, y)| noise_2d.eval(x, y))
+            .fold((0.0f64, 0.0f64), |(min, max), v| (min.min(v), max.max(v)));
+        assert!(min > -0.65 && max < 0.65, "2D noise went from {min} to {max}");
+        assert!(min < -0.3 && max > 0.3, "2D noise only went from {min} to {max}");
+
+        let noise_4d = SimplexTileable4D::new(seed, [30, 30, 30, 30]);
+        let (min, max) = points(300, 30.0)
+            .map(|(x, y)| noise_4d.eval(x, y, x * 0.37 + y * 0.61, y * 0.29 - x * 0.53))
+            .fold((0.0f64, 0.0f64), |(min, max), v| (min.min(v), max.max(v)));
+        assert!(min >= -1.0 && max <= 1.0, "4D noise went from {min} to {max}");
+        assert!(min < -0.3 && max > 0.3, "4D noise only went from {min} to {max}");
+    }
+}
+
+#[test]
+fn same_seed_same_noise() {
+    let a = SimplexTileable2D::new(5344545, 30, 30);
+    let b = SimplexTileable2D::new(5344545, 30, 30);
+    let c = SimplexTileable2D::new(42, 30, 30);
+    assert!(points(40, 30.0).all(|(x, y)| a.eval(x, y) == b.eval(x, y)));
+    assert!(points(40, 30.0).any(|(x, y)| a.eval(x, y) != c.eval(x, y)));
+
+    let a = SimplexTileable4D::new(5344545, [30, 30, 30, 30]);
+    let b = SimplexTileable4D::new(5344545, [30, 30, 30, 30]);
+    let c = SimplexTileable4D::new(42, [30, 30, 30, 30]);
+    assert!(points(40, 30.0).all(|(x, y)| a.eval(x, y, y, x) == b.eval(x, y, y, x)));
+    assert!(points(40, 30.0).any(|(x, y)| a.eval(x, y, y, x) != c.eval(x, y, y, x)));
+}
+
+#[test]
+#[should_panic(expected = "multiples of 3")]
+fn periods_must_line_up_with_the_lattice() {
+    SimplexTileable2D::new(5344545, 10, 9);
+}
@@ -1,10 +1,25 @@
-use simulation::{world::{World, SlotPosition, Tile, Ambient}, life::{vision::look, tick}, util::FastRandom};
+use simulation::{world::{World, SlotPosition, Ambient, Entity, LevelEvent, creature::Creature}, life::{vision::look_lit, tick, combat::fight, feeding::eat_carried, items::{pick_up, drop_item}, terrain::{dig, build}}, util::FastRandom};
+
+/// What stepping towards a slot does
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum Mode {
+    Walk,
+    Dig,
+    Build,
+}
 
 pub struct Player<const SIZE: usize, const H: usize> {
     pub world: World<SIZE, H>,
     pub ambient: Ambient,
     pub vision: Vec<f32>,
     pub radius: usize,
+    /// Debug view of the scent left around
+    pub show_scent: bool,
+    pub show_inventory: bool,
+    pub mode: Mode,
+    /// Where the ambient and vision were last worked out from
+    seen_from: SlotPosition,
+    random: FastRandom,
 }
 
 impl<const SIZE: usize, const H: usize> Player<SIZE, H> {
@@ -12,18 +27,23 @@ impl<const SIZE: usize, const H: usize> Player<SIZE, H> {
     pub fn new(world: World<SIZE, H>, radius: usize) -> Self {
         let pos = world.player_position;
         let ambient = world.get_ambient_at(&pos, radius);
-        let vision = look(&world[pos.level], pos.x, pos.y, radius);
+        let vision = look_lit(&world[pos.level], pos.x, pos.y, radius);
         Self {
             world,
             ambient,
             vision,
             radius,
+            show_scent: false,
+            show_inventory: false,
+            mode: Mode::Walk,
+            seen_from: pos,
+            random: FastRandom::new_from_sys_time(),
         }
     }
 
     fn move_to(&mut self, position: SlotPosition) {
         let old_pos = self.world.player_position;
-        self.world[&old_pos].entity.expect("Player out of sync with tracked position");
+        self.world[&old_pos].entity.as_ref().expect("Player out of sync with tracked position");
         self.world.swap_entities(old_pos, position);
         self.world.player_position = position;
     }
@@ -32,24 +52,73 @@ impl<const SIZE: usize, const H: usize> Player<SIZE, H> {
         let new_pos = self.world.position_relative_to_player(xoff, yoff);
 
         let next_slot = &self.world[&new_pos];
-        if (next_slot.tile.is_floor() || next_slot.tile.is_swimmable()) && matches!(next_slot.entity, None) {
+        let pos = self.world.player_position;
+        match self.mode {
+            Mode::Dig => return dig(&mut self.world.levels[pos.level], &self.world.items, (pos.x, pos.y), (new_pos.x, new_pos.y)),
+            Mode::Build => return build(&mut self.world.levels[pos.level], &self.world.items, (pos.x, pos.y), (new_pos.x, new_pos.y)),
+            Mode::Walk => {},
+        }
+        if let Some(Entity::Creature(_)) = next_slot.entity {
+            self.attack(new_pos);
+            true
+        } else if let Some(Entity::Object(_)) = next_slot.entity {
+            pick_up(&mut self.world.levels[pos.level], (pos.x, pos.y), (new_pos.x, new_pos.y))
+        } else if (next_slot.tile.is_floor() || next_slot.tile.is_swimmable()) && matches!(next_slot.entity, None) {
             self.move_to(new_pos);
             true
         } else { false }
     }
 
-    pub fn try_enter(&mut self) -> bool {
-        let old_pos = &self.world.player_position;
-        match self.world[old_pos].tile {
-            Tile::GotoOverworld => {
-                self.move_to(old_pos.to_level(0));
-                true
+    fn attack(&mut self, position: SlotPosition) {
+        let pos = self.world.player_position;
+        let level = &mut self.world.levels[pos.level];
+        fight(level, &self.world.species, &mut self.random, (pos.x, pos.y), (position.x, position.y));
+    }
+
+    pub fn creature(&self) -> Option<&Creature> {
+        match &self.world[&self.world.player_position].entity {
+            Some(Entity::Creature(c)) => Some(c),
+            _ => None,
+        }
+    }
+
+    /// Puts the item at `index` of the inventory down next to the player
+    pub fn drop_item(&mut self, index: usize) -> bool {
+        let pos = self.world.player_position;
+        drop_item(&mut self.world.levels[pos.level], (pos.x, pos.y), index)
+    }
+
+    /// Eats the first edible item in the inventory
+    pub fn eat(&mut self) -> bool {
+        let pos = self.world.player_position;
+        let species = &self.world.species;
+        let items = &self.world.items;
+        match &mut self.world.levels[pos.level][(pos.x, pos.y)].entity {
+            Some(Entity::Creature(c)) => {
+                let diet = species[&c.species].diet;
+                eat_carried(c, items, &diet)
             },
-            Tile::GotoUnderworld => {
-                self.move_to(old_pos.to_level(1));
+            _ => false,
+        }
+    }
+
+    pub fn is_dead(&self) -> bool {
+        match &self.world[&self.world.player_position].entity {
+            Some(Entity::Creature(c)) => c.body.health == 0,
+            _ => true,
+        }
+    }
+
+    /// Takes the stairs the player is standing on, arriving next to them if someone's in the way
+    pub fn try_enter(&mut self) -> bool {
+        let old_pos = self.world.player_position;
+        self.world[&old_pos].entity.as_ref().expect("Player out of sync with tracked position");
+        match self.world.take_portal(&old_pos) {
+            Some(arrival) => {
+                self.world.player_position = arrival;
                 true
             },
-            _ => false,
+            None => false,
         }
     }
 
@@ -58,11 +127,31 @@ impl<const SIZE: usize, const H: usize> Player<SIZE, H> {
     pub fn step_left(&mut self) -> bool { self.try_tp(-1, 0) }
     pub fn step_right(&mut self) -> bool { self.try_tp(1, 0) }
 
+    /// Lets the world tick, then works the ambient and vision out again if what they depend on changed
     pub fn tick(&mut self) {
+        let events = tick(&mut self.world, &mut self.random);
         let pos = self.world.player_position;
-        self.ambient = self.world.get_ambient_at(&pos, self.radius);
-        self.vision = look(&self.world[pos.level], pos.x, pos.y, self.radius);
-        let mut random = FastRandom::new_from_sys_time();
-        tick(&mut self.world, &mut random);
+        let moved = pos != self.seen_from;
+        let level = &self.world[pos.level];
+        let mut tiles_changed = false;
+        let mut light_changed = false;
+        for (l, event) in events {
+            if l != pos.level {
+                continue;
+            }
+            match event {
+                LevelEvent::TileChanged { position, .. } => if level.distance((pos.x, pos.y), position) <= self.radius * 2 {
+                    tiles_changed = true;
+                },
+                LevelEvent::LightChanged => light_changed = true,
+            }
+        }
+        if moved || tiles_changed {
+            self.ambient = self.world.get_ambient_at(&pos, self.radius);
+        }
+        if moved || tiles_changed || light_changed {
+            self.vision = look_lit(&self.world[pos.level], pos.x, pos.y, self.radius);
+        }
+        self.seen_from = pos;
     }
 }
\ No newline at end of file
@@ -7,12 +7,15 @@ use super::{GRASS_BG, SAND_BG, SNOW_BG};
 
 pub fn tile_to_ascii_sprite(tile: &Tile, random: &mut FastRandom) -> AsciiSprite {
     match tile {
-        Tile::GotoUnderworld => AsciiSprite { fg: RGB::WHITE, bg: RGB::BLACK, index: '%' as _ },
-        Tile::GotoOverworld  => AsciiSprite { fg: RGB::WHITE, bg: RGB::BLACK, index: '%' as _ },
+        Tile::StairsDown => AsciiSprite { fg: RGB::WHITE, bg: RGB::BLACK, index: '%' as _ },
+        Tile::StairsUp  => AsciiSprite { fg: RGB::WHITE, bg: RGB::BLACK, index: '%' as _ },
 
         Tile::OverworldGravel => AsciiSprite { fg: rgb(0.55, 0.55, 0.5), bg: rgb(0.45, 0.45, 0.4), index: ['.', '.', '.', ' ', ' ', '_', '^'][random.next_less_than(7) as usize] as _ },
         Tile::OverworldScraps => AsciiSprite { fg: rgb(0.5, 0.5, 0.5), bg: rgb(0.4, 0.4, 0.4), index: ['"', '\'', '`', '"'][random.next_less_than(4) as usize] as _ },
         Tile::OverworldWater  => AsciiSprite { fg: rgb(0.55, 0.5, 0.25), bg: rgb(0.15, 0.22, 0.15), index: ['~', ' ', ' '][random.next_less_than(3) as usize] as _ },
+        Tile::Sand            => AsciiSprite { fg: rgb(0.85, 0.7, 0.5), bg: SAND_BG, index: ['.', ' ', ' ', ',', '~'][random.next_less_than(5) as usize] as _ },
+        Tile::Snow            => AsciiSprite { fg: rgb(0.75, 0.8, 0.85), bg: SNOW_BG, index: ['.', ' ', ' ', '\'', '*'][random.next_less_than(5) as usize] as _ },
+        Tile::Trench          => AsciiSprite { fg: rgb(0.4, 0.38, 0.3), bg: rgb(0.3, 0.28, 0.22), index: ['.', ',', ' '][random.next_less_than(3) as usize] as _ },
         Tile::OverworldDeepWater => AsciiSprite { fg: rgb(0.45, 0.5, 0.2), bg: rgb(0.2, 0.18, 0.0), index: ['~', '~', '-', ' ', ' '][random.next_less_than(5) as usize] as _ },
 
         Tile::Void           => AsciiSprite { fg: RGB::BLACK, bg: RGB::BLACK, index: 0 },
@@ -36,5 +39,10 @@ pub fn tile_to_ascii_sprite(tile: &Tile, random: &mut FastRandom) -> AsciiSprite
         
         Tile::DungeonFloor   => AsciiSprite { fg: rgb(0.5, 0.5, 0.5), bg: RGB::BLACK, index: ['.', '.', '.', ',', ',', '_'][random.next_less_than(6) as usize] as _ },
         Tile::DungeonWall    => AsciiSprite { fg: rgb(0.8, 0.8, 0.8), bg: RGB::BLACK, index: '#' as _ },
+        Tile::Doorway        => AsciiSprite { fg: rgb(0.6, 0.45, 0.25), bg: RGB::BLACK, index: '+' as _ },
+        Tile::Altar          => AsciiSprite { fg: rgb(1.0, 0.85, 0.4), bg: RGB::BLACK, index: 15 },
+        Tile::Corridor       => AsciiSprite { fg: rgb(0.4, 0.4, 0.35), bg: rgb(0.12, 0.12, 0.1), index: ['.', '.', ' '][random.next_less_than(3) as usize] as _ },
+
+        Tile::StoneWall      => AsciiSprite { fg: rgb(0.6, 0.6, 0.6), bg: rgb(0.3, 0.3, 0.3), index: '#' as _ },
     }
 }
\ No newline at end of file
@@ -1,7 +1,7 @@
 
 use engine::{AsciiSprite, Context, Key, Game, UpdateResult, rgb, RGB, util::draw_text, rgb_gray};
-use crate::{TEXT_SIZE, player::Player, scene::world::adapter::tile_to_ascii_sprite};
-use simulation::{world::{World, Entity}, util::FastRandom};
+use crate::{TEXT_SIZE, player::{Player, Mode}, scene::{world::adapter::tile_to_ascii_sprite, main_menu::create_main_menu_scene}};
+use simulation::{world::{World, Entity, Scent}, util::FastRandom};
 
 mod adapter;
 
@@ -20,7 +20,35 @@ fn draw_gui<const SIZE: usize, const H: usize>(
     const bg: RGB = rgb(0.1, 0.1, 0.1);
     const fg: RGB = RGB::WHITE;
 
-    draw_text(player.ambient.to_string().as_str(), x, y, xoff, yoff, bg, fg)
+    let mode = match player.mode {
+        Mode::Walk => "",
+        Mode::Dig => " | digging",
+        Mode::Build => " | building",
+    };
+    draw_text(format!("{} | {}{}", player.ambient, player.world.clock, mode).as_str(), x, y, xoff, yoff, bg, fg)
+        .or_else(|| if player.show_inventory { draw_inventory(player, x, y) } else { None })
+}
+
+fn draw_inventory<const SIZE: usize, const H: usize>(
+    player: &Player<SIZE, H>,
+    x: usize,
+    y: usize,
+) -> Option<AsciiSprite> {
+    const xoff: usize = 1;
+    const yoff: usize = 3;
+    const bg: RGB = rgb(0.1, 0.1, 0.1);
+    const fg: RGB = RGB::WHITE;
+
+    let items = player.creature().map(|c| c.inventory.items.as_slice()).unwrap_or_default();
+    if y == yoff {
+        return draw_text("Inventory: E to eat, 1-8 to drop", x, y, xoff, yoff, bg, fg);
+    }
+    if items.is_empty() {
+        return draw_text("nothing", x, y, xoff, yoff + 1, bg, fg);
+    }
+    let i = y.checked_sub(yoff + 1)?;
+    let item = &player.world.items[items.get(i)?];
+    draw_text(format!("{} {} {}", i + 1, item.symbol, item.name).as_str(), x, y, xoff, y, bg, fg)
 }
 
 fn get_char_at<const SIZE: usize, const H: usize>(
@@ -37,7 +65,7 @@ fn get_char_at<const SIZE: usize, const H: usize>(
         x as isize - TEXT_SIZE.0 as isize / 2,
         y as isize - TEXT_SIZE.1 as isize / 2,
     );
-    let slot = player.world[position];
+    let slot = &player.world[position];
     let vision = (get_vision(player, x, y) * 1.4).min(1.0);
     fn random_offset(random: &mut FastRandom, max_offset: f32) -> f32 {
         (random.next_less_than(256) as f32 / 255.0 - 0.5) * max_offset
@@ -47,12 +75,15 @@ fn get_char_at<const SIZE: usize, const H: usize>(
     let max_offset = 0.35 - 0.3 * vision;
     let tile = tile_to_ascii_sprite(&slot.tile, &mut random);
     let r = vision + random_offset(&mut random, max_offset);
-    let bg = tile.bg * rgb_gray(r);
-    match slot.entity {
+    let mut bg = tile.bg * rgb_gray(r);
+    if player.show_scent {
+        bg = scent_overlay(bg, slot.scent);
+    }
+    match &slot.entity {
         Some(e) => {
             let char = match e {
                 Entity::Creature(c) => player.world.species[&c.species].symbol,
-                Entity::Object(_) => 'o',
+                Entity::Object(o) => player.world.items[o].symbol,
             };
             let fg = if bg.squared_perceived_lightness() > 0.25 {
                 RGB::BLACK
@@ -70,6 +101,15 @@ fn get_char_at<const SIZE: usize, const H: usize>(
     }
 }
 
+/// Predators' scent in red, their prey's in green
+fn scent_overlay(bg: RGB, scent: Scent) -> RGB {
+    rgb(
+        bg.r.max(scent.predator as f32 / 64.0).min(1.0),
+        bg.g.max(scent.prey as f32 / 64.0).min(1.0),
+        bg.b,
+    )
+}
+
 fn get_vision<const SIZE: usize, const H: usize>(player: &Player<SIZE, H>, x: usize, y: usize) -> f32 {
     let x = x as isize - TEXT_SIZE.0 as isize / 2;
     let y = y as isize - TEXT_SIZE.1 as isize / 2;
@@ -89,10 +129,34 @@ fn on_tick<const SIZE: usize, const H: usize>(player: &mut Player<SIZE, H>, cont
             Key::A => { player.step_left(); },
             Key::D => { player.step_right(); },
             Key::Space => { player.try_enter(); },
+            Key::O => {
+                player.show_scent = !player.show_scent;
+                return UpdateResult::Update
+            },
+            Key::I => {
+                player.show_inventory = !player.show_inventory;
+                return UpdateResult::Update
+            },
+            Key::E => { player.eat(); },
+            Key::G => {
+                player.mode = if player.mode == Mode::Dig { Mode::Walk } else { Mode::Dig };
+                return UpdateResult::Update
+            },
+            Key::B => {
+                player.mode = if player.mode == Mode::Build { Mode::Walk } else { Mode::Build };
+                return UpdateResult::Update
+            },
+            Key::Num1 | Key::Num2 | Key::Num3 | Key::Num4 |
+            Key::Num5 | Key::Num6 | Key::Num7 | Key::Num8 if player.show_inventory => {
+                player.drop_item(k as usize - Key::Num1 as usize);
+            },
             _ => return UpdateResult::NoChange
         },
         None => {}
     }
     player.tick();
+    if player.is_dead() {
+        return UpdateResult::SwitchScene(Box::new(create_main_menu_scene()));
+    }
     UpdateResult::Update
 }
\ No newline at end of file
@@ -1,8 +1,8 @@
-use std::{sync::Mutex, fs::{read_dir, read_to_string}, path::Path, ops::Mul};
+use std::{sync::Mutex, fs::{read_dir, read_to_string}, path::Path, ops::Mul, collections::HashMap};
 
 use engine::{AsciiSprite, RGB, rgb, UpdateResult, Loading, util::draw_text};
 
-use simulation::world::{World, creature::{SpeciesTemplate, Diet, SpeciesMap, SpeciesID}};
+use simulation::world::{World, ItemID, ItemMap, ItemTemplate, gen::WorldGenConfig, creature::{SpeciesTemplate, Diet, SpeciesMap, SpeciesID}};
 use toml::value::Table;
 
 use crate::TEXT_SIZE;
@@ -13,6 +13,7 @@ use super::world::create_world_scene;
 #[derive(Debug)]
 pub enum LoadingTask {
     GeneratingWorld,
+    PlacingItems,
     CreatingLife,
 }
 
@@ -20,6 +21,7 @@ impl LoadingTask {
     fn get_label(&self) -> &str {
         match self {
             LoadingTask::GeneratingWorld => "Generating world",
+            LoadingTask::PlacingItems => "Placing items",
             LoadingTask::CreatingLife => "Creating life",
         }
     }
@@ -30,7 +32,7 @@ pub struct LoadingState {
 }
 
 const WORLD_SIZE: usize = 128 * 3;
-const LEVELS: usize = 2;
+const LEVELS: usize = 4;
 
 pub fn create_world_loading_scene() -> Loading<LoadingState, World<WORLD_SIZE, LEVELS>> {
     let state = LoadingState {
@@ -53,12 +55,15 @@ fn get_char_at(state: &LoadingState, x: usize, y: usize) -> AsciiSprite {
 
 fn load(state: &Mutex<LoadingState>) -> World<WORLD_SIZE, LEVELS> {
     let seed = 5344545;
-    let mut world = World::<WORLD_SIZE, LEVELS>::generate(seed);
+    let config = load_world_gen_config(Path::new("assets/worldgen.toml"));
+    let mut world = World::<WORLD_SIZE, LEVELS>::generate(seed, &config);
+    state.lock().unwrap().task = LoadingTask::PlacingItems;
+    world.scatter_items(seed, &config, load_item_templates(Path::new("assets/items")));
     state.lock().unwrap().task = LoadingTask::CreatingLife;
     let mut species = load_species_templates(Path::new("assets/species"));
     let player_species_id = SpeciesID(species.keys().len() as u64);
     species.insert(player_species_id, load_player_template(Path::new("assets/player.toml")));
-    world.create_life(seed, species, player_species_id);
+    world.create_life(seed, &config, species, player_species_id);
     world
 }
 
@@ -66,6 +71,69 @@ fn on_loaded(world: World<WORLD_SIZE, LEVELS>) -> UpdateResult {
     UpdateResult::SwitchScene(Box::new(create_world_scene(world)))
 }
 
+/// Anything missing from the file keeps its default, and so does everything if there's no file
+fn load_world_gen_config(path: &Path) -> WorldGenConfig {
+    let text = match read_to_string(path) {
+        Ok(text) => text,
+        Err(_) => return WorldGenConfig::default(),
+    };
+    let table: Table = text.as_str().parse::<Table>().expect("Couldn't parse world generation file");
+    let default = WorldGenConfig::default();
+    let float = |section: &str, key: &str, default: f64| table.get(section)
+        .and_then(|t| t.get(key))
+        .and_then(|x| x.as_float())
+        .unwrap_or(default);
+    let integer = |section: &str, key: &str, default: usize| table.get(section)
+        .and_then(|t| t.get(key))
+        .and_then(|x| x.as_integer())
+        .map(|x| usize::try_from(x).unwrap_or_else(|_| panic!("Invalid world generation file: {key} in [{section}] can't be negative")))
+        .unwrap_or(default);
+    let config = WorldGenConfig {
+        deep_water_level: float("overworld", "deep_water_level", default.deep_water_level),
+        river_sparseness: integer("overworld", "river_sparseness", default.river_sparseness),
+        river_max_length: integer("overworld", "river_max_length", default.river_max_length),
+        river_source_height: float("overworld", "river_source_height", default.river_source_height),
+        max_lake_size: integer("overworld", "max_lake_size", default.max_lake_size),
+        cave_wall_density: float("underworld", "cave_wall_density", default.cave_wall_density),
+        deep_cavern_wall_density: float("underworld", "deep_cavern_wall_density", default.deep_cavern_wall_density),
+        cave_quadrant_size: integer("underworld", "cave_quadrant_size", default.cave_quadrant_size),
+        dungeon_quadrant_size: integer("underworld", "dungeon_quadrant_size", default.dungeon_quadrant_size),
+        worm_cave_sparseness: integer("underworld", "worm_cave_sparseness", default.worm_cave_sparseness),
+        portal_sparseness: integer("portals", "sparseness", default.portal_sparseness),
+        min_stairs: integer("portals", "min_stairs", default.min_stairs),
+        creature_sparseness: integer("population", "creature_sparseness", default.creature_sparseness),
+        item_sparseness: integer("population", "item_sparseness", default.item_sparseness),
+    };
+    if let Err(e) = config.validate() {
+        panic!("Invalid world generation file: {e}");
+    }
+    config
+}
+
+fn load_item_templates(directory: &Path) -> ItemMap {
+    let items = read_dir(directory)
+        .expect("Missing items directory")
+        .enumerate()
+        .map(|(i, res)| {
+            let entry = res.unwrap();
+            (ItemID(i as u64), load_item_template(entry.path().as_path()))
+        });
+    ItemMap::from_iter(items)
+}
+
+fn load_item_template(path: &Path) -> ItemTemplate {
+    let table: Table = read_to_string(path).unwrap().as_str().parse::<Table>().expect("Couldn't parse item file");
+    ItemTemplate {
+        name: path.file_stem().unwrap().to_str().unwrap().to_string(),
+        symbol: table["symbol"].as_str()
+            .and_then(|x| x.bytes().nth(0))
+            .and_then(|x| Some(x as char))
+            .or(Some('_')).unwrap(),
+        nutrition: table.get("nutrition").and_then(|x| x.as_float()).unwrap_or(0.0).mul(255.0) as u8,
+        abundance: table.get("abundance").and_then(|x| x.as_integer()).unwrap_or(0) as u8,
+    }
+}
+
 fn load_species_templates(directory: &Path) -> SpeciesMap {
     let species = read_dir(directory)
         .expect("Missing species directory")
@@ -80,7 +148,7 @@ fn load_species_templates(directory: &Path) -> SpeciesMap {
 fn load_species_template(path: &Path) -> SpeciesTemplate {
     let table: Table = read_to_string(path).unwrap().as_str().parse::<Table>().expect("Couldn't parse species file");
     SpeciesTemplate {
-        name: path.file_name().unwrap().to_str().unwrap().to_string(),
+        name: path.file_stem().unwrap().to_str().unwrap().to_string(),
         symbol: table["symbol"].as_str()
             .and_then(|x| x.bytes().nth(0))
             .and_then(|x| Some(x as char))
@@ -97,9 +165,18 @@ fn load_species_template(path: &Path) -> SpeciesTemplate {
         max_nutrition: table["body"]["max_nutrition"].as_float().or(Some(0.0)).unwrap().mul(255.0) as u8,
         smelliness: table["body"]["smelliness"].as_float().or(Some(0.0)).unwrap().mul(255.0) as u8,
         strength: table["body"]["strength"].as_float().or(Some(0.0)).unwrap().mul(255.0) as u8,
+        speed: table["body"]["speed"].as_float().or(Some(0.0)).unwrap().mul(255.0) as u8,
+        can_dig: table["body"].get("can_dig").and_then(|x| x.as_bool()).unwrap_or(false),
         awareness: table["behavior"]["awareness"].as_float().or(Some(0.0)).unwrap().mul(255.0) as u8,
         curiosity: table["behavior"]["curiosity"].as_float().or(Some(0.0)).unwrap().mul(255.0) as u8,
         friendliness: table["behavior"]["friendliness"].as_float().or(Some(0.0)).unwrap().mul(127.0) as i8,
+        nocturnal: table["behavior"].get("nocturnal").and_then(|x| x.as_bool()).unwrap_or(false),
+        relations: table.get("relations")
+            .and_then(|t| t.as_table())
+            .map(|t| t.iter()
+                .map(|(name, v)| (name.clone(), v.as_float().or(Some(0.0)).unwrap().mul(127.0) as i8))
+                .collect())
+            .unwrap_or_default(),
     }
 }
 
@@ -121,8 +198,12 @@ fn load_player_template(path: &Path) -> SpeciesTemplate {
         max_nutrition: table["body"]["max_nutrition"].as_float().or(Some(0.0)).unwrap().mul(255.0) as u8,
         smelliness: table["body"]["smelliness"].as_float().or(Some(0.0)).unwrap().mul(255.0) as u8,
         strength: table["body"]["strength"].as_float().or(Some(0.0)).unwrap().mul(255.0) as u8,
+        speed: table["body"]["speed"].as_float().or(Some(0.0)).unwrap().mul(255.0) as u8,
+        can_dig: true,
         awareness: 0,
         curiosity: 0,
         friendliness: 0,
+        nocturnal: false,
+        relations: HashMap::new(),
     }
 }
\ No newline at end of file